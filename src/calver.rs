@@ -0,0 +1,272 @@
+use chrono::{Datelike, NaiveDate};
+use regex::Regex;
+use std::cmp::Ordering;
+
+// =============================================================================================
+// VERSIONADO DE CALENDARIO (CalVer)
+// =============================================================================================
+
+/// Elementos de un formato CalVer (ver https://calver.org).
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    /// Año completo: 2006, 2016, 2106.
+    FullYear,
+    /// Año corto: 6, 16, 106.
+    ShortYear,
+    /// Año corto con relleno: 06, 16, 106.
+    PaddedYear,
+    /// Mes: 1, 2 ... 11, 12.
+    ShortMonth,
+    /// Mes con relleno: 01, 02 ... 11, 12.
+    PaddedMonth,
+    /// Semana ISO: 1, 2 ... 52.
+    ShortWeek,
+    /// Semana ISO con relleno: 01, 02 ... 52.
+    PaddedWeek,
+    /// Día: 1, 2 ... 31.
+    ShortDay,
+    /// Día con relleno: 01, 02 ... 31.
+    PaddedDay,
+    /// Contador que se reinicia cuando cambia la parte de fecha.
+    Micro,
+    Literal(char),
+}
+
+// El orden importa: los tokens más largos se comprueban primero.
+const TOKENS: [(&str, Token); 10] = [
+    ("YYYY", Token::FullYear),
+    ("MICRO", Token::Micro),
+    ("YY", Token::ShortYear),
+    ("0Y", Token::PaddedYear),
+    ("MM", Token::ShortMonth),
+    ("0M", Token::PaddedMonth),
+    ("WW", Token::ShortWeek),
+    ("0W", Token::PaddedWeek),
+    ("DD", Token::ShortDay),
+    ("0D", Token::PaddedDay),
+];
+
+impl Token {
+    fn regex(&self) -> String {
+        match self {
+            Token::FullYear => r"(\d{4})".to_string(),
+            Token::ShortYear => r"(\d{1,3})".to_string(),
+            Token::PaddedYear => r"(\d{2,3})".to_string(),
+            Token::ShortMonth | Token::ShortWeek | Token::ShortDay => r"(\d{1,2})".to_string(),
+            Token::PaddedMonth | Token::PaddedWeek | Token::PaddedDay => r"(\d{2})".to_string(),
+            Token::Micro => r"(\d+)".to_string(),
+            Token::Literal(c) => regex::escape(&c.to_string()),
+        }
+    }
+
+    fn is_date(&self) -> bool {
+        !matches!(self, Token::Micro | Token::Literal(_))
+    }
+
+    fn is_week(&self) -> bool {
+        matches!(self, Token::ShortWeek | Token::PaddedWeek)
+    }
+
+    /// Valor del token para `date`. En formatos con semana el año es el de la semana ISO
+    /// (el 2024-12-30 pertenece a la semana 1 de 2025).
+    fn date_value(&self, date: NaiveDate, week_based: bool) -> Option<u64> {
+        let year = if week_based { date.iso_week().year() } else { date.year() };
+        let value = match self {
+            Token::FullYear => year as u64,
+            Token::ShortYear | Token::PaddedYear => (year - 2000) as u64,
+            Token::ShortMonth | Token::PaddedMonth => date.month() as u64,
+            Token::ShortWeek | Token::PaddedWeek => date.iso_week().week() as u64,
+            Token::ShortDay | Token::PaddedDay => date.day() as u64,
+            Token::Micro | Token::Literal(_) => return None,
+        };
+        Some(value)
+    }
+
+    fn render(&self, value: u64) -> String {
+        match self {
+            Token::PaddedYear | Token::PaddedMonth | Token::PaddedWeek | Token::PaddedDay => {
+                format!("{:02}", value)
+            }
+            Token::Literal(c) => c.to_string(),
+            _ => value.to_string(),
+        }
+    }
+}
+
+/// Formato CalVer compilado a partir de una cadena como `YYYY.MM.MICRO` o `YY.0M.DD`.
+#[derive(Debug, Clone)]
+pub struct CalVerFormat {
+    format: String,
+    tokens: Vec<Token>,
+    regex: Regex,
+}
+
+impl CalVerFormat {
+    pub fn parse(format: &str) -> Result<Self, String> {
+        let mut tokens = Vec::new();
+        let mut rest = format;
+        while !rest.is_empty() {
+            match TOKENS.iter().find(|(name, _)| rest.starts_with(name)) {
+                Some((name, token)) => {
+                    tokens.push(token.clone());
+                    rest = &rest[name.len()..];
+                }
+                None => {
+                    let c = rest.chars().next().unwrap();
+                    if c.is_ascii_alphanumeric() {
+                        return Err(format!("Unknown token at '{}' in CalVer format '{}'", rest, format));
+                    }
+                    tokens.push(Token::Literal(c));
+                    rest = &rest[c.len_utf8()..];
+                }
+            }
+        }
+        if !tokens.iter().any(Token::is_date) {
+            return Err(format!("CalVer format '{}' has no date tokens", format));
+        }
+        if tokens.iter().filter(|t| **t == Token::Micro).count() > 1 {
+            return Err(format!("CalVer format '{}' has more than one MICRO token", format));
+        }
+
+        let pattern: String = tokens.iter().map(Token::regex).collect();
+        let regex = Regex::new(&format!("^{}$", pattern))
+            .map_err(|e| format!("Error compiling CalVer format '{}': {}", format, e))?;
+
+        Ok(Self {
+            format: format.to_string(),
+            tokens,
+            regex,
+        })
+    }
+
+    /// Devuelve los valores numéricos de cada token de `version`, en orden.
+    fn values(&self, version: &str) -> Result<Vec<u64>, String> {
+        let captures = self.regex.captures(version.trim()).ok_or_else(|| {
+            format!("Version '{}' does not match CalVer format '{}'", version, self.format)
+        })?;
+        captures
+            .iter()
+            .skip(1)
+            .flatten()
+            .map(|m| {
+                m.as_str()
+                    .parse::<u64>()
+                    .map_err(|_| format!("Invalid number '{}' in version '{}'", m.as_str(), version))
+            })
+            .collect()
+    }
+
+    fn value_tokens(&self) -> impl Iterator<Item = &Token> {
+        self.tokens.iter().filter(|t| !matches!(t, Token::Literal(_)))
+    }
+
+    fn render(&self, values: &[u64]) -> String {
+        let mut values = values.iter();
+        self.tokens
+            .iter()
+            .map(|token| match token {
+                Token::Literal(_) => token.render(0),
+                _ => token.render(*values.next().unwrap_or(&0)),
+            })
+            .collect()
+    }
+
+    /// Comprueba que `version` sigue el formato y la devuelve normalizada.
+    pub fn validate(&self, version: &str) -> Result<String, String> {
+        let values = self.values(version)?;
+        Ok(self.render(&values))
+    }
+
+    /// Compara dos versiones del mismo formato token a token.
+    pub fn compare(&self, a: &str, b: &str) -> Result<Ordering, String> {
+        Ok(self.values(a)?.cmp(&self.values(b)?))
+    }
+
+    /// Calcula la siguiente versión para la fecha `today`: si la parte de fecha no ha
+    /// cambiado se incrementa MICRO; si ha cambiado, MICRO se reinicia a 0.
+    pub fn next_version(&self, current_version: &str, today: NaiveDate) -> Result<String, String> {
+        let current = self.values(current_version)?;
+        let tokens: Vec<&Token> = self.value_tokens().collect();
+
+        let date_part = |values: &[u64]| -> Vec<u64> {
+            tokens
+                .iter()
+                .zip(values)
+                .filter(|(t, _)| t.is_date())
+                .map(|(_, v)| *v)
+                .collect()
+        };
+        let week_based = tokens.iter().any(|t| t.is_week());
+        let today_values: Vec<u64> = tokens
+            .iter()
+            .map(|t| t.date_value(today, week_based).unwrap_or(0))
+            .collect();
+
+        let next: Vec<u64> = match date_part(&today_values).cmp(&date_part(&current)) {
+            Ordering::Greater => today_values,
+            Ordering::Equal => {
+                let micro = tokens.iter().position(|t| **t == Token::Micro).ok_or_else(|| {
+                    format!(
+                        "Version {} already uses today's date and format '{}' has no MICRO token",
+                        current_version, self.format
+                    )
+                })?;
+                let mut next = current.clone();
+                next[micro] += 1;
+                next
+            }
+            Ordering::Less => {
+                return Err(format!(
+                    "The date {} is older than the date of the current version {}",
+                    today, current_version
+                ));
+            }
+        };
+        Ok(self.render(&next))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn same_period_bumps_micro() {
+        let format = CalVerFormat::parse("YYYY.MM.MICRO").unwrap();
+        assert_eq!(format.next_version("2025.3.0", date(2025, 3, 14)).unwrap(), "2025.3.1");
+        assert_eq!(format.next_version("2025.3.7", date(2025, 3, 31)).unwrap(), "2025.3.8");
+    }
+
+    #[test]
+    fn new_month_resets_micro() {
+        let format = CalVerFormat::parse("YYYY.MM.MICRO").unwrap();
+        assert_eq!(format.next_version("2025.3.7", date(2025, 4, 1)).unwrap(), "2025.4.0");
+        let padded = CalVerFormat::parse("YY.0M.MICRO").unwrap();
+        assert_eq!(padded.next_version("24.12.3", date(2025, 1, 2)).unwrap(), "25.01.0");
+    }
+
+    #[test]
+    fn same_day_without_micro_fails() {
+        let format = CalVerFormat::parse("YY.0M.DD").unwrap();
+        assert_eq!(format.next_version("25.03.13", date(2025, 3, 14)).unwrap(), "25.03.14");
+        assert!(format.next_version("25.03.14", date(2025, 3, 14)).is_err());
+    }
+
+    #[test]
+    fn current_date_in_the_future_fails() {
+        let format = CalVerFormat::parse("YYYY.MM.MICRO").unwrap();
+        assert!(format.next_version("2025.4.0", date(2025, 3, 31)).is_err());
+    }
+
+    #[test]
+    fn week_formats_use_the_iso_week_year() {
+        let format = CalVerFormat::parse("YYYY.0W.MICRO").unwrap();
+        assert_eq!(format.next_version("2024.52.3", date(2024, 12, 30)).unwrap(), "2025.01.0");
+        let format = CalVerFormat::parse("YYYY.MM.MICRO").unwrap();
+        assert_eq!(format.next_version("2024.11.3", date(2024, 12, 30)).unwrap(), "2024.12.0");
+    }
+}
@@ -0,0 +1,400 @@
+use std::{collections::HashMap, io, path::Path};
+use toml_edit::{DocumentMut, Item, TableLike, Value};
+
+use crate::utils::{SimulatedFile, read_text_file};
+
+// =============================================================================================
+// INTEGRACIÓN CON CARGO (WORKSPACES)
+// =============================================================================================
+
+const ROOT_MANIFEST: &str = "Cargo.toml";
+const LOCKFILE: &str = "Cargo.lock";
+const DEPENDENCY_TABLES: [&str; 3] = ["dependencies", "dev-dependencies", "build-dependencies"];
+
+/// Documento TOML en edición junto con su contenido original y el número de cambios.
+struct Manifest {
+    path: String,
+    original: String,
+    document: DocumentMut,
+    changes: usize,
+}
+
+impl Manifest {
+    fn parse(path: &str, content: String) -> Result<Self, io::Error> {
+        let document = content.parse::<DocumentMut>().map_err(|e| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("File '{}' is not valid TOML: {}", path, e),
+            )
+        })?;
+        Ok(Self {
+            path: path.to_string(),
+            original: content,
+            document,
+            changes: 0,
+        })
+    }
+
+    fn into_simulated(self) -> SimulatedFile {
+        SimulatedFile {
+            path: self.path,
+            modified: self.document.to_string(),
+            original: self.original,
+            matches: self.changes,
+        }
+    }
+}
+
+// Lee el archivo partiendo de los cambios ya simulados por reglas anteriores.
+pub(crate) async fn load(path: &str, contents: &HashMap<String, String>) -> Result<String, io::Error> {
+    match contents.get(path) {
+        Some(content) => Ok(content.clone()),
+        None => read_text_file(path).await,
+    }
+}
+
+fn string_array<'a>(table: &'a dyn TableLike, key: &str) -> Vec<&'a str> {
+    table
+        .get(key)
+        .and_then(Item::as_array)
+        .map(|array| array.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default()
+}
+
+/// Descubre los manifiestos del workspace: la raíz (si tiene `[package]`) y cada miembro de
+/// `workspace.members` (admite globs), salvo los de `workspace.exclude`.
+fn workspace_manifests(root: &DocumentMut) -> Result<Vec<String>, io::Error> {
+    let mut manifests = Vec::new();
+    if root.contains_key("package") {
+        manifests.push(ROOT_MANIFEST.to_string());
+    }
+    let Some(workspace) = root.get("workspace").and_then(Item::as_table_like) else {
+        return Ok(manifests);
+    };
+
+    let excluded = string_array(workspace, "exclude");
+    for pattern in string_array(workspace, "members") {
+        let paths = glob::glob(pattern).map_err(|e| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Invalid workspace member pattern '{}': {}", pattern, e),
+            )
+        })?;
+        for directory in paths.filter_map(Result::ok) {
+            let manifest = directory.join(ROOT_MANIFEST);
+            let member = directory.to_string_lossy();
+            if !manifest.is_file() || excluded.iter().any(|exclude| exclude.trim_end_matches('/') == member) {
+                continue;
+            }
+            let manifest = manifest.to_string_lossy().into_owned();
+            if !manifests.contains(&manifest) {
+                manifests.push(manifest);
+            }
+        }
+    }
+    Ok(manifests)
+}
+
+/// Cambia el valor de cadena `current_version` por `new_version` conservando su formato.
+pub(crate) fn bump_string(value: &mut Value, current_version: &str, new_version: &str) -> bool {
+    if value.as_str() != Some(current_version) {
+        return false;
+    }
+    let decor = value.decor().clone();
+    *value = new_version.into();
+    *value.decor_mut() = decor;
+    true
+}
+
+/// Ajusta el requisito de versión de una dependencia interna para que admita `new_version`.
+/// Un requisito simple con versión completa (`0.3.1`, `^0.3.1`, `~0.3.1`, `=0.3.1` o
+/// `>=0.3.1`) se reescribe conservando el operador. Cualquier otro (`>0.3.0`, `^1.2`,
+/// `>=0.3, <0.4`, `0.3.*`...) se deja igual si `new_version` ya lo cumple; si no, solo se
+/// reescribe cuando es un único comparador con uno de esos operadores, y en otro caso es un
+/// error, porque reescribirlo podría dejarlo insatisfacible.
+fn bump_requirement(requirement: &str, new_version: &str) -> Result<Option<String>, String> {
+    let requirement = requirement.trim();
+    let base = requirement.trim_start_matches(['^', '~', '=', '>', '<']);
+    let operator = &requirement[..requirement.len() - base.len()];
+    let rewritable = ["", "^", "~", "=", ">="].contains(&operator) && !base.contains([',', '*', ' ']);
+    if rewritable && semver::Version::parse(base).is_ok() {
+        return Ok((base != new_version).then(|| format!("{}{}", operator, new_version)));
+    }
+
+    let parsed = semver::VersionReq::parse(requirement)
+        .map_err(|e| format!("invalid version requirement '{}': {}", requirement, e))?;
+    let version = semver::Version::parse(new_version)
+        .map_err(|e| format!("version {} is not valid SemVer: {}", new_version, e))?;
+    if parsed.matches(&version) {
+        Ok(None)
+    } else if rewritable {
+        Ok(Some(format!("{}{}", operator, new_version)))
+    } else {
+        Err(format!(
+            "version requirement '{}' is not satisfied by {} and cannot be updated automatically",
+            requirement, new_version
+        ))
+    }
+}
+
+// Incrementa `table.version` si vale `current_version` (o indica que hereda del workspace).
+fn bump_package_version(
+    table: &mut dyn TableLike,
+    current_version: &str,
+    new_version: &str,
+    workspace_bumped: bool,
+) -> (usize, bool) {
+    match table.get_mut("version") {
+        Some(Item::Value(value)) => {
+            let bumped = bump_string(value, current_version, new_version);
+            (bumped as usize, bumped || value.as_str() == Some(new_version))
+        }
+        Some(item) => {
+            let inherits = item
+                .as_table_like()
+                .and_then(|version| version.get("workspace"))
+                .and_then(Item::as_bool)
+                == Some(true);
+            (0, inherits && workspace_bumped)
+        }
+        None => (0, false),
+    }
+}
+
+// Actualiza el requisito de versión de las dependencias por `path` hacia crates hermanos.
+fn bump_sibling_dependencies(
+    table: &mut dyn TableLike,
+    siblings: &[String],
+    new_version: &str,
+) -> Result<usize, String> {
+    let mut changes = 0;
+    for (key, item) in table.iter_mut() {
+        let Some(dependency) = item.as_table_like_mut() else {
+            continue;
+        };
+        let name = dependency
+            .get("package")
+            .and_then(Item::as_str)
+            .unwrap_or(key.get())
+            .to_string();
+        if !siblings.contains(&name) || !dependency.contains_key("path") {
+            continue;
+        }
+        let Some(Item::Value(value)) = dependency.get_mut("version") else {
+            continue;
+        };
+        let requirement = match value.as_str() {
+            Some(requirement) => bump_requirement(requirement, new_version)
+                .map_err(|e| format!("Dependency '{}': {}", name, e))?,
+            None => None,
+        };
+        if let Some(requirement) = requirement {
+            let decor = value.decor().clone();
+            *value = requirement.into();
+            *value.decor_mut() = decor;
+            changes += 1;
+        }
+    }
+    Ok(changes)
+}
+
+// Aplica `f` a cada tabla de dependencias del manifiesto, incluidas las de `target.*` y
+// `workspace.dependencies`.
+fn for_each_dependency_table(document: &mut DocumentMut, mut f: impl FnMut(&mut dyn TableLike)) {
+    for name in DEPENDENCY_TABLES {
+        if let Some(table) = document.get_mut(name).and_then(Item::as_table_like_mut) {
+            f(table);
+        }
+    }
+    if let Some(targets) = document.get_mut("target").and_then(Item::as_table_like_mut) {
+        for (_, target) in targets.iter_mut() {
+            let Some(target) = target.as_table_like_mut() else {
+                continue;
+            };
+            for name in DEPENDENCY_TABLES {
+                if let Some(table) = target.get_mut(name).and_then(Item::as_table_like_mut) {
+                    f(table);
+                }
+            }
+        }
+    }
+    if let Some(table) = document
+        .get_mut("workspace")
+        .and_then(Item::as_table_like_mut)
+        .and_then(|workspace| workspace.get_mut("dependencies"))
+        .and_then(Item::as_table_like_mut)
+    {
+        f(table);
+    }
+}
+
+/// Actualiza las entradas de los crates del workspace en `Cargo.lock` (las que no tienen
+/// `source`), sin invocar a cargo.
+pub fn bump_lockfile_packages(
+    document: &mut DocumentMut,
+    names: &[String],
+    current_version: &str,
+    new_version: &str,
+) -> usize {
+    let Some(packages) = document.get_mut("package").and_then(Item::as_array_of_tables_mut) else {
+        return 0;
+    };
+    packages
+        .iter_mut()
+        .filter(|package| {
+            !package.contains_key("source")
+                && package
+                    .get("name")
+                    .and_then(Item::as_str)
+                    .is_some_and(|name| names.iter().any(|n| n == name))
+        })
+        .filter_map(|package| package.get_mut("version").and_then(Item::as_value_mut))
+        .map(|version| bump_string(version, current_version, new_version))
+        .filter(|bumped| *bumped)
+        .count()
+}
+
+/// Simula el cambio de versión en todo el workspace de Cargo: `package.version` de cada
+/// miembro (o `workspace.package.version`), los requisitos de versión de las dependencias
+/// por `path` entre miembros y sus entradas en `Cargo.lock`. Solo se devuelven los archivos
+/// que cambian.
+pub async fn simulate_cargo_workspace(
+    contents: &HashMap<String, String>,
+    current_version: &str,
+    new_version: &str,
+) -> Vec<(String, Result<SimulatedFile, io::Error>)> {
+    let root = match load(ROOT_MANIFEST, contents).await.and_then(|c| Manifest::parse(ROOT_MANIFEST, c)) {
+        Ok(root) => root,
+        Err(e) => return vec![(ROOT_MANIFEST.to_string(), Err(e))],
+    };
+    let paths = match workspace_manifests(&root.document) {
+        Ok(paths) => paths,
+        Err(e) => return vec![(ROOT_MANIFEST.to_string(), Err(e))],
+    };
+
+    let mut results = Vec::new();
+    let mut manifests = vec![root];
+    for path in paths.iter().filter(|path| path.as_str() != ROOT_MANIFEST) {
+        match load(path, contents).await.and_then(|c| Manifest::parse(path, c)) {
+            Ok(manifest) => manifests.push(manifest),
+            Err(e) => results.push((path.clone(), Err(e))),
+        }
+    }
+
+    // 1. Versión compartida del workspace (`workspace.package.version`).
+    let root = &mut manifests[0];
+    let mut workspace_bumped = false;
+    if let Some(Item::Value(version)) = root
+        .document
+        .get_mut("workspace")
+        .and_then(Item::as_table_like_mut)
+        .and_then(|workspace| workspace.get_mut("package"))
+        .and_then(Item::as_table_like_mut)
+        .and_then(|package| package.get_mut("version"))
+    {
+        if bump_string(version, current_version, new_version) {
+            root.changes += 1;
+        }
+        workspace_bumped = version.as_str() == Some(new_version);
+    }
+
+    // 2. Versión de cada paquete; se recogen los crates que quedan en la nueva versión.
+    let mut siblings = Vec::new();
+    for manifest in manifests.iter_mut() {
+        if !paths.contains(&manifest.path) {
+            continue;
+        }
+        let Some(package) = manifest.document.get_mut("package").and_then(Item::as_table_like_mut) else {
+            continue;
+        };
+        let (changes, released) = bump_package_version(package, current_version, new_version, workspace_bumped);
+        manifest.changes += changes;
+        if released && let Some(name) = package.get("name").and_then(Item::as_str) {
+            siblings.push(name.to_string());
+        }
+    }
+
+    // 3. Requisitos de versión de las dependencias entre miembros.
+    let mut errors = HashMap::new();
+    for manifest in manifests.iter_mut() {
+        let mut changes = 0;
+        for_each_dependency_table(&mut manifest.document, |table| {
+            match bump_sibling_dependencies(table, &siblings, new_version) {
+                Ok(bumped) => changes += bumped,
+                Err(e) => {
+                    errors.entry(manifest.path.clone()).or_insert(e);
+                }
+            }
+        });
+        manifest.changes += changes;
+    }
+
+    for manifest in manifests {
+        if let Some(e) = errors.remove(&manifest.path) {
+            results.push((
+                manifest.path.clone(),
+                Err(io::Error::new(io::ErrorKind::InvalidData, format!("File '{}': {}", manifest.path, e))),
+            ));
+        } else if manifest.changes > 0 {
+            results.push((manifest.path.clone(), Ok(manifest.into_simulated())));
+        }
+    }
+
+    // 4. Cargo.lock (opcional).
+    if Path::new(LOCKFILE).is_file() {
+        match load(LOCKFILE, contents).await.and_then(|c| Manifest::parse(LOCKFILE, c)) {
+            Ok(mut lockfile) => {
+                lockfile.changes = bump_lockfile_packages(&mut lockfile.document, &siblings, current_version, new_version);
+                if lockfile.changes > 0 {
+                    results.push((LOCKFILE.to_string(), Ok(lockfile.into_simulated())));
+                }
+            }
+            Err(e) => results.push((LOCKFILE.to_string(), Err(e))),
+        }
+    }
+
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bump(requirement: &str, new_version: &str) -> Result<Option<String>, String> {
+        bump_requirement(requirement, new_version)
+    }
+
+    #[test]
+    fn simple_requirements_keep_their_operator() {
+        assert_eq!(bump("0.3.1", "0.4.0").unwrap().as_deref(), Some("0.4.0"));
+        assert_eq!(bump("^0.3.1", "0.4.0").unwrap().as_deref(), Some("^0.4.0"));
+        assert_eq!(bump("~0.3.1", "0.3.2").unwrap().as_deref(), Some("~0.3.2"));
+        assert_eq!(bump("=0.3.1", "0.4.0").unwrap().as_deref(), Some("=0.4.0"));
+        assert_eq!(bump(">=0.3.1", "0.4.0").unwrap().as_deref(), Some(">=0.4.0"));
+        assert_eq!(bump("^0.4.0", "0.4.0").unwrap(), None);
+    }
+
+    #[test]
+    fn partial_caret_and_tilde_requirements() {
+        assert_eq!(bump("^1.2", "1.5.0").unwrap(), None);
+        assert_eq!(bump("^1.2", "2.0.0").unwrap().as_deref(), Some("^2.0.0"));
+        assert_eq!(bump("~1", "1.9.0").unwrap(), None);
+        assert_eq!(bump("0.3", "0.3.2").unwrap(), None);
+        assert_eq!(bump("0.3", "0.4.0").unwrap().as_deref(), Some("0.4.0"));
+    }
+
+    #[test]
+    fn compound_requirements_are_kept_or_rejected() {
+        assert_eq!(bump(">=0.3, <0.4", "0.3.2").unwrap(), None);
+        assert!(bump(">=0.3, <0.4", "0.4.0").is_err());
+        assert_eq!(bump(">0.3.0", "0.4.0").unwrap(), None);
+        assert!(bump("<0.4.0", "0.4.0").is_err());
+    }
+
+    #[test]
+    fn wildcard_requirements() {
+        assert_eq!(bump("*", "2.0.0").unwrap(), None);
+        assert_eq!(bump("0.3.*", "0.3.5").unwrap(), None);
+        assert!(bump("0.3.*", "0.4.0").is_err());
+    }
+}
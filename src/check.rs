@@ -0,0 +1,206 @@
+use regex::Regex;
+use std::io;
+
+use crate::config::{Config, Replace};
+use crate::structured::read_structured_value;
+use crate::template::TemplateContext;
+use crate::utils::{MatchBounds, expand_replace_files, read_text_file};
+
+// =============================================================================================
+// CHECK: AUDITORÍA DE CONSISTENCIA
+// =============================================================================================
+
+// Marcador temporal para sustituir `{{current_version}}` por una RegEx genérica.
+const VERSION_MARKER: &str = "\u{1}VERSION\u{1}";
+// Cualquier versión plausible (SemVer, PEP 440, CalVer...).
+const ANY_VERSION: &str = "[0-9][0-9A-Za-z.+!_-]*";
+
+/// Resultado de comprobar un archivo contra la versión actual.
+pub enum CheckStatus {
+    Ok,
+    /// El patrón o la clave no aparecen (o el archivo no existe).
+    Missing(String),
+    /// El patrón aparece, pero con otra versión (se listan las encontradas).
+    Mismatched(Vec<String>),
+    /// El patrón aparece más veces de las que admite la regla (se listan las líneas).
+    Ambiguous(Vec<usize>),
+}
+
+impl CheckStatus {
+    pub fn code(&self) -> &'static str {
+        match self {
+            CheckStatus::Ok => "ok",
+            CheckStatus::Missing(_) => "missing",
+            CheckStatus::Mismatched(_) => "mismatched",
+            CheckStatus::Ambiguous(_) => "ambiguous",
+        }
+    }
+
+    pub fn is_ok(&self) -> bool {
+        matches!(self, CheckStatus::Ok)
+    }
+}
+
+pub struct FileCheck {
+    pub file: String,
+    pub status: CheckStatus,
+}
+
+fn line_of(content: &str, offset: usize) -> usize {
+    content[..offset].matches('\n').count() + 1
+}
+
+fn compile(pattern: &str) -> Result<Regex, String> {
+    Regex::new(&format!("(?m){}", pattern)).map_err(|e| format!("Invalid pattern '{}': {}", pattern, e))
+}
+
+// Busca el patrón con cualquier versión en lugar de `{{current_version}}` para distinguir
+// una versión desactualizada de un patrón ausente.
+fn find_other_versions(content: &str, replace: &Replace, context: &TemplateContext) -> Result<Vec<String>, String> {
+    let template = replace.pattern.replace("{{current_version}}", VERSION_MARKER);
+    let pattern = context
+        .render_pattern(&template)?
+        .replacen(VERSION_MARKER, &format!("(?P<found>{})", ANY_VERSION), 1)
+        .replace(VERSION_MARKER, ANY_VERSION);
+    if !pattern.contains("(?P<found>") {
+        return Ok(Vec::new());
+    }
+
+    let mut found: Vec<String> = Vec::new();
+    for captures in compile(&pattern)?.captures_iter(content) {
+        let version = captures["found"].to_string();
+        if !found.contains(&version) {
+            found.push(version);
+        }
+    }
+    Ok(found)
+}
+
+fn check_regex(
+    content: &str,
+    replace: &Replace,
+    bounds: MatchBounds,
+    context: &TemplateContext,
+) -> Result<CheckStatus, String> {
+    let re = compile(&context.render_pattern(&replace.pattern)?)?;
+    let lines: Vec<usize> = re.find_iter(content).map(|m| line_of(content, m.start())).collect();
+
+    // Solo hay ambigüedad si la regla fija un máximo (`count` o `max`): sin él, `upgrade`
+    // acepta cualquier número de coincidencias y `check` no debe contradecirlo.
+    if bounds.contains(lines.len()) {
+        return Ok(CheckStatus::Ok);
+    }
+    if bounds.max.is_some_and(|max| lines.len() > max) {
+        return Ok(CheckStatus::Ambiguous(lines));
+    }
+    if !lines.is_empty() {
+        return Ok(CheckStatus::Missing(format!(
+            "{} match(es), expected {}",
+            lines.len(),
+            bounds.describe()
+        )));
+    }
+
+    let found = find_other_versions(content, replace, context)?;
+    Ok(if found.is_empty() {
+        CheckStatus::Missing("pattern not found".to_string())
+    } else {
+        CheckStatus::Mismatched(found)
+    })
+}
+
+/// Evalúa cada regla `Replace` contra `current_version` sin modificar ningún archivo.
+pub async fn check_files(config: &Config) -> Vec<FileCheck> {
+    let current_version = config.current_version.as_str();
+    let context = TemplateContext::new(config, current_version, current_version).await;
+    let mut checks = Vec::new();
+
+    for replace in &config.replaces {
+        let expanded = replace.structured_path().and_then(|structured| {
+            Ok((structured, replace.match_bounds()?, expand_replace_files(replace)?))
+        });
+        let (structured, bounds, files) = match expanded {
+            Ok(expanded) => expanded,
+            Err(e) => {
+                checks.push(FileCheck {
+                    file: replace.file.to_string(),
+                    status: CheckStatus::Missing(e),
+                });
+                continue;
+            }
+        };
+
+        for file in files {
+            let content = match read_text_file(&file).await {
+                Ok(content) => content,
+                Err(e) => {
+                    let reason = match e.kind() {
+                        io::ErrorKind::NotFound => "file not found".to_string(),
+                        _ => e.to_string(),
+                    };
+                    checks.push(FileCheck {
+                        file,
+                        status: CheckStatus::Missing(reason),
+                    });
+                    continue;
+                }
+            };
+
+            let status = match structured {
+                Some((format, key_path)) => match read_structured_value(format, &file, &content, key_path) {
+                    Ok(Some(value)) if value == current_version => CheckStatus::Ok,
+                    Ok(Some(value)) => CheckStatus::Mismatched(vec![value]),
+                    Ok(None) => CheckStatus::Missing(format!("key '{}' not found", key_path)),
+                    Err(e) => CheckStatus::Missing(e.to_string()),
+                },
+                None => check_regex(&content, replace, bounds, &context).unwrap_or_else(CheckStatus::Missing),
+            };
+            checks.push(FileCheck { file, status });
+        }
+    }
+
+    checks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONTENT: &str = "version = \"1.2.3\"\nfoo = { version = \"1.2.3\" }\nversion = \"1.2.3\"\n";
+
+    async fn check(replace: Replace) -> CheckStatus {
+        let config = Config::new("1.2.3".to_string());
+        let context = TemplateContext::new(&config, "1.2.3", "1.2.3").await;
+        check_regex(CONTENT, &replace, replace.match_bounds().unwrap(), &context).unwrap()
+    }
+
+    #[tokio::test]
+    async fn default_rule_accepts_any_number_of_matches() {
+        assert!(check(Replace::default()).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn min_only_rule_has_no_ceiling() {
+        let replace = Replace { min: Some(2), ..Replace::default() };
+        assert!(check(replace).await.is_ok());
+        let replace = Replace { min: Some(4), ..Replace::default() };
+        assert!(matches!(check(replace).await, CheckStatus::Missing(_)));
+    }
+
+    #[tokio::test]
+    async fn count_and_max_report_extra_matches_as_ambiguous() {
+        let replace = Replace { count: Some(1), ..Replace::default() };
+        assert!(matches!(check(replace).await, CheckStatus::Ambiguous(lines) if lines == [1, 2, 3]));
+        let replace = Replace { max: Some(2), ..Replace::default() };
+        assert!(matches!(check(replace).await, CheckStatus::Ambiguous(_)));
+    }
+
+    #[tokio::test]
+    async fn other_version_is_reported_as_mismatched() {
+        let config = Config::new("1.2.4".to_string());
+        let context = TemplateContext::new(&config, "1.2.4", "1.2.4").await;
+        let replace = Replace::default();
+        let status = check_regex(CONTENT, &replace, replace.match_bounds().unwrap(), &context).unwrap();
+        assert!(matches!(status, CheckStatus::Mismatched(found) if found == ["1.2.3"]));
+    }
+}
@@ -1,4 +1,4 @@
-use clap::{Parser, Args, Subcommand, ArgAction};
+use clap::{Parser, Args, Subcommand, ArgAction, ValueEnum};
 
 const DESCRIPTION: &str = env!("CARGO_PKG_DESCRIPTION");
 const VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -11,25 +11,71 @@ pub struct Cli {
     /// Enables debug messages for the 'vampus' application.
     pub debug: bool,
 
+    #[arg(long, global = true, action = ArgAction::SetTrue)]
+    /// Allows changing the version with uncommitted changes in the git working tree.
+    pub allow_dirty: bool,
+
+    #[arg(long, global = true, value_enum, default_value_t = OutputFormat::Human)]
+    /// Output format: human-readable text or a single JSON document for scripts.
+    pub output: OutputFormat,
+
     #[command(subcommand)]
     pub command: Commands,
 }
 
+/// Formato de salida de los comandos.
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+pub enum OutputFormat {
+    Human,
+    Json,
+}
+
 #[derive(Subcommand)]
 pub enum Commands {
     /// Increments the project version (updates the version number in the configuration).
     // Usamos la nueva estructura compartida
-    Upgrade(VersionArgs),
+    Upgrade(UpgradeArgs),
     
-    /// Shows the resulting project version without applying the change.
-    Preview(VersionArgs),
+    /// Performs a dry run: computes the resulting version and simulates every replacement without writing.
+    Preview(PreviewArgs),
 
     /// Sets the project version back to a calculated previous version and updates the files.
-    // Usamos la nueva estructura compartida
-    Downgrade(VersionArgs), 
+    Downgrade(DowngradeArgs),
+
+    /// Sets the project version to an explicit value and updates the files.
+    Set(SetArgs),
     
     /// Displays the current version of the project.
     Show,
+
+    /// Verifies that every tracked file contains the current version without modifying anything.
+    Check,
+
+    /// Detects the project manifests and creates a '.vampus.yml' for them.
+    Init(InitArgs),
+}
+
+impl Commands {
+    /// Nombre del comando tal y como aparece en el informe JSON.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Commands::Upgrade(_) => "upgrade",
+            Commands::Preview(_) => "preview",
+            Commands::Downgrade(_) => "downgrade",
+            Commands::Set(_) => "set",
+            Commands::Show => "show",
+            Commands::Check => "check",
+            Commands::Init(_) => "init",
+        }
+    }
+}
+
+#[derive(Args)]
+/// Arguments for the 'init' command.
+pub struct InitArgs {
+    /// Overwrites an existing configuration file.
+    #[arg(long, action = ArgAction::SetTrue)]
+    pub force: bool,
 }
 
 #[derive(Args)]
@@ -49,4 +95,113 @@ pub struct VersionArgs {
     /// Increments/Decrements the MAJOR version (breaking changes).
     #[arg(long, action = ArgAction::SetTrue, group = "VERSION_TYPE")]
     pub major: bool,
+
+    /// Increments/Decrements any named part of a custom 'parse' pattern (custom scheme).
+    #[arg(long, value_name = "NAME", group = "VERSION_TYPE")]
+    pub part: Option<String>,
+
+    /// Infers the change type from the Conventional Commits since the last tag (upgrade, preview).
+    #[arg(long, action = ArgAction::SetTrue, group = "VERSION_TYPE")]
+    pub auto: bool,
+}
+
+#[derive(Args)]
+/// Arguments for the 'set' command.
+pub struct SetArgs {
+    /// The target version (e.g. 1.0.0 or 2.0.0-rc.1).
+    pub version: String,
+
+    /// Allows setting a version that is not greater than the current one.
+    #[arg(long, action = ArgAction::SetTrue)]
+    pub force: bool,
+
+    #[command(flatten)]
+    pub diff: DiffArgs,
+}
+
+#[derive(Args)]
+/// Arguments for the 'downgrade' command.
+pub struct DowngradeArgs {
+    #[command(flatten)]
+    pub version: VersionArgs,
+
+    #[command(flatten)]
+    pub diff: DiffArgs,
+}
+
+#[derive(Args)]
+/// Options to review the planned changes as a unified diff.
+pub struct DiffArgs {
+    /// Shows a colored unified diff of every planned change, including the config file.
+    #[arg(long, action = ArgAction::SetTrue)]
+    pub diff: bool,
+
+    /// Number of context lines around each change in the diff.
+    #[arg(long, value_name = "LINES", default_value_t = 3, requires = "diff")]
+    pub diff_context: usize,
+}
+
+#[derive(Args)]
+/// Arguments for commands that move the version forward (upgrade, preview).
+pub struct UpgradeArgs {
+    #[command(flatten)]
+    pub version: VersionArgs,
+
+    #[command(flatten)]
+    pub pre_release: PreReleaseArgs,
+
+    #[command(flatten)]
+    pub diff: DiffArgs,
+}
+
+#[derive(Args)]
+/// Arguments for the 'preview' command (dry run of upgrade, downgrade or set).
+pub struct PreviewArgs {
+    #[command(flatten)]
+    pub version: VersionArgs,
+
+    #[command(flatten)]
+    pub pre_release: PreReleaseArgs,
+
+    /// Previews a downgrade instead of an upgrade.
+    #[arg(long, action = ArgAction::SetTrue, conflicts_with_all = ["PRE_RELEASE", "set", "auto"])]
+    pub downgrade: bool,
+
+    /// Previews setting an explicit version instead of an upgrade.
+    #[arg(long, value_name = "VERSION", conflicts_with_all = ["VERSION_TYPE", "PRE_RELEASE"])]
+    pub set: Option<String>,
+
+    /// With --set, allows a version that is not greater than the current one.
+    #[arg(long, action = ArgAction::SetTrue, requires = "set")]
+    pub force: bool,
+
+    #[command(flatten)]
+    pub diff: DiffArgs,
+}
+
+#[derive(Args)]
+/// Pre-release workflow options (alpha -> beta -> rc -> final).
+pub struct PreReleaseArgs {
+    // --- Opciones de Pre-release Mutuamente Excluyentes ---
+
+    /// Starts a pre-release on the next PATCH/MINOR/MAJOR, or increments the counter of the
+    /// current one. Without LABEL, the current label (or the first configured one) is used.
+    #[arg(long, value_name = "LABEL", num_args = 0..=1, group = "PRE_RELEASE")]
+    pub pre: Option<Option<String>>,
+
+    /// Promotes the current pre-release to the next label in `pre_release_labels`.
+    #[arg(long, action = ArgAction::SetTrue, group = "PRE_RELEASE", conflicts_with_all = ["patch", "minor", "major", "part", "auto"])]
+    pub promote: bool,
+
+    /// Strips the pre-release part, turning the current pre-release into the final version.
+    #[arg(long, action = ArgAction::SetTrue, group = "PRE_RELEASE", conflicts_with_all = ["patch", "minor", "major", "part", "auto"])]
+    pub release: bool,
+
+    /// Adds or increments the post-release segment (pep440 scheme).
+    #[arg(long, action = ArgAction::SetTrue, group = "PRE_RELEASE", conflicts_with_all = ["patch", "minor", "major", "part", "auto"])]
+    pub post: bool,
+
+    /// Starts or increments a development release segment (pep440 scheme).
+    #[arg(long, action = ArgAction::SetTrue, group = "PRE_RELEASE")]
+    pub dev: bool,
 }
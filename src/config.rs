@@ -2,15 +2,86 @@ use serde::{
     Serialize,
     Deserialize
 };
-use std::path::PathBuf;
+use std::{fmt, io, path::{Path, PathBuf}};
 use tracing::{debug, error};
 
+use crate::calver::CalVerFormat;
+use crate::format::VersionFormat;
+use crate::structured::StructuredFormat;
+use crate::utils::{MatchBounds, apply_replacement};
+use crate::version::Version;
+
+/// Esquema de versionado usado para interpretar y calcular las versiones.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Scheme {
+    SemVer,
+    CalVer,
+    Custom,
+    Pep440,
+}
+
+/// Origen de la versión actual: el archivo de configuración o las etiquetas de git.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VersionSource {
+    Config,
+    Git,
+}
+
+/// Archivos de una regla `Replace`: una ruta o un glob (admite `**`), o una lista de ellos.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum FileSpec {
+    Single(String),
+    Multiple(Vec<String>),
+}
+
+impl FileSpec {
+    pub fn patterns(&self) -> &[String] {
+        match self {
+            FileSpec::Single(pattern) => std::slice::from_ref(pattern),
+            FileSpec::Multiple(patterns) => patterns,
+        }
+    }
+}
+
+impl fmt::Display for FileSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.patterns().join(", "))
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Replace {
     #[serde(default = "get_default_file")]
-    pub file: String,
-    #[serde(default = "get_default_pattern")]
+    pub file: FileSpec,
+    #[serde(default = "get_default_pattern", skip_serializing_if = "is_default_pattern")]
     pub pattern: String,
+    // Texto que sustituye a cada coincidencia de `pattern`. Sin él, solo se cambia la versión.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replace: Option<String>,
+    // Ruta de claves de un archivo TOML, JSON, YAML o XML (`package.version`, `.version`,
+    // `/project/version`). Si está presente, se edita ese valor conservando el formato y se
+    // ignoran `pattern` y `replace`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub toml_path: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub json_path: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub yaml_path: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub xml_path: Option<String>,
+    // Globs de archivos que se descartan tras expandir `file`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exclude: Vec<String>,
+    // Número de coincidencias esperado en cada archivo: exacto (`count`) o un rango.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub count: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max: Option<usize>,
 }
 
 impl Replace {
@@ -18,6 +89,58 @@ impl Replace {
         Self {
             file: get_default_file(),
             pattern: get_default_pattern(),
+            replace: None,
+            toml_path: None,
+            json_path: None,
+            yaml_path: None,
+            xml_path: None,
+            exclude: Vec::new(),
+            count: None,
+            min: None,
+            max: None,
+        }
+    }
+
+    /// Formato y ruta de claves si la regla es estructurada. Solo se admite una ruta por regla.
+    pub fn structured_path(&self) -> Result<Option<(StructuredFormat, &str)>, String> {
+        let paths: Vec<(StructuredFormat, &str)> = [
+            (StructuredFormat::Toml, &self.toml_path),
+            (StructuredFormat::Json, &self.json_path),
+            (StructuredFormat::Yaml, &self.yaml_path),
+            (StructuredFormat::Xml, &self.xml_path),
+        ]
+        .into_iter()
+        .filter_map(|(format, path)| path.as_deref().map(|path| (format, path)))
+        .collect();
+
+        match paths.as_slice() {
+            [] => Ok(None),
+            [path] => Ok(Some(*path)),
+            _ => Err(format!(
+                "Replace rule for '{}' can only use one of 'toml_path', 'json_path', 'yaml_path' or 'xml_path'",
+                self.file
+            )),
+        }
+    }
+
+    /// Coincidencias admitidas por archivo. Sin `count`, `min` ni `max` basta con una o más.
+    pub fn match_bounds(&self) -> Result<MatchBounds, String> {
+        match (self.count, self.min, self.max) {
+            (Some(_), Some(_), _) | (Some(_), _, Some(_)) => Err(format!(
+                "Replace rule for '{}' cannot combine 'count' with 'min' or 'max'",
+                self.file
+            )),
+            (Some(count), None, None) => Ok(MatchBounds { min: count, max: Some(count) }),
+            (None, min, max) => {
+                let min = min.unwrap_or(1);
+                if max.is_some_and(|max| max < min) {
+                    return Err(format!(
+                        "Replace rule for '{}' has 'max' lower than 'min' ({})",
+                        self.file, min
+                    ));
+                }
+                Ok(MatchBounds { min, max })
+            }
         }
     }
 }
@@ -26,15 +149,89 @@ impl Replace {
 pub struct Config {
     #[serde(default = "get_default_current_version")]
     pub current_version: String,
+    #[serde(default = "get_default_version_source", skip_serializing_if = "is_default_version_source")]
+    pub version_source: VersionSource,
+    #[serde(default = "get_default_tag_prefix", skip_serializing_if = "is_default_tag_prefix")]
+    pub tag_prefix: String,
+    #[serde(default = "get_default_scheme", skip_serializing_if = "is_default_scheme")]
+    pub scheme: Scheme,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub calver_format: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parse: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub serialize: Vec<String>,
     #[serde(default = "get_default_replaces")]
     pub replaces: Vec<Replace>,
+    // Actualiza todos los miembros del workspace de Cargo, sus dependencias internas y Cargo.lock.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub cargo_workspace: bool,
+    // Actualiza la entrada del propio proyecto en package-lock.json, Cargo.lock y uv.lock.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub sync_lockfiles: bool,
+    #[serde(default = "get_default_pre_release_labels", skip_serializing_if = "is_default_pre_release_labels")]
+    pub pre_release_labels: Vec<String>,
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub commit: bool,
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub tag: bool,
+    #[serde(default = "get_default_tag_name", skip_serializing_if = "is_default_tag_name")]
+    pub tag_name: String,
+    #[serde(default = "get_default_message", skip_serializing_if = "is_default_message")]
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allow_dirty: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allowed_branches: Vec<String>,
+}
+
+// Los campos que conservan su valor por defecto no se escriben en `.vampus.yml`, para que
+// cada cambio de versión solo modifique `current_version`.
+fn is_false(value: &bool) -> bool {
+    !*value
+}
+fn is_default_version_source(source: &VersionSource) -> bool {
+    *source == get_default_version_source()
+}
+fn is_default_tag_prefix(prefix: &String) -> bool {
+    *prefix == get_default_tag_prefix()
+}
+fn is_default_scheme(scheme: &Scheme) -> bool {
+    *scheme == get_default_scheme()
+}
+fn is_default_pre_release_labels(labels: &Vec<String>) -> bool {
+    *labels == get_default_pre_release_labels()
+}
+fn is_default_tag_name(tag_name: &String) -> bool {
+    *tag_name == get_default_tag_name()
+}
+fn is_default_message(message: &String) -> bool {
+    *message == get_default_message()
 }
 
 fn get_default_current_version() -> String {
     "0.1.0".to_string()
 }
-fn get_default_file() -> String {
-    "Cargo.toml".to_string()
+fn get_default_version_source() -> VersionSource {
+    VersionSource::Config
+}
+fn get_default_tag_prefix() -> String {
+    "v".to_string()
+}
+fn get_default_scheme() -> Scheme {
+    Scheme::SemVer
+}
+fn get_default_calver_format() -> String {
+    "YYYY.MM.MICRO".to_string()
+}
+fn get_default_parse() -> String {
+    r"(?P<major>\d+)\.(?P<minor>\d+)\.(?P<patch>\d+)".to_string()
+}
+fn get_default_serialize() -> Vec<String> {
+    vec!["{{major}}.{{minor}}.{{patch}}".to_string()]
+}
+fn get_default_file() -> FileSpec {
+    FileSpec::Single("Cargo.toml".to_string())
 }
 
 // MODIFICADO: Patrón simple. La envoltura de captura se hace en main.rs.
@@ -42,27 +239,93 @@ fn get_default_pattern() -> String {
     "version = \"{{current_version}}\"".to_string()
 }
 
+fn is_default_pattern(pattern: &String) -> bool {
+    *pattern == get_default_pattern()
+}
+
 fn get_default_replaces() -> Vec<Replace> {
     vec![Replace::default()]
 }
 
+// Orden en el que se promocionan las pre-releases con `--promote`.
+fn get_default_pre_release_labels() -> Vec<String> {
+    vec!["alpha".to_string(), "beta".to_string(), "rc".to_string()]
+}
+
+fn get_default_tag_name() -> String {
+    "v{{new_version}}".to_string()
+}
+
+fn get_default_message() -> String {
+    "Bump version: {{current_version}} → {{new_version}}".to_string()
+}
+
 impl Config {
     fn default() -> Self{
         Self{
             current_version: get_default_current_version(),
+            version_source: get_default_version_source(),
+            tag_prefix: get_default_tag_prefix(),
+            scheme: get_default_scheme(),
+            calver_format: None,
+            parse: None,
+            serialize: Vec::new(),
             replaces: get_default_replaces(),
+            cargo_workspace: false,
+            sync_lockfiles: false,
+            pre_release_labels: get_default_pre_release_labels(),
+            commit: false,
+            tag: false,
+            tag_name: get_default_tag_name(),
+            message: get_default_message(),
+            allow_dirty: Vec::new(),
+            allowed_branches: Vec::new(),
+        }
+    }
+    /// Configuración por defecto con la versión actual indicada (usada por `init`).
+    pub fn new(current_version: String) -> Self {
+        Self {
+            current_version,
+            ..Self::default()
         }
     }
-    pub async fn write_default(file: &PathBuf){
-        let default = Self::default();
-        let _ = tokio::fs::write(
-            file,
-            serde_yaml::to_string(&default).unwrap().as_bytes()).await;
+
+    /// Interpreta `current_version` como una versión SemVer 2.0.
+    pub fn version(&self) -> Result<Version, String> {
+        self.current_version.parse::<Version>()
+    }
+
+    /// Compila el formato CalVer configurado (por defecto `YYYY.MM.MICRO`).
+    pub fn calver(&self) -> Result<CalVerFormat, String> {
+        CalVerFormat::parse(
+            self.calver_format
+                .clone()
+                .unwrap_or_else(get_default_calver_format)
+                .as_str(),
+        )
+    }
+
+    /// Compila el formato personalizado definido por `parse` y `serialize`.
+    pub fn format(&self) -> Result<VersionFormat, String> {
+        let parse = self.parse.clone().unwrap_or_else(get_default_parse);
+        let serialize = if self.serialize.is_empty() {
+            get_default_serialize()
+        } else {
+            self.serialize.clone()
+        };
+        VersionFormat::new(&parse, &serialize)
     }
 
     pub async fn read(file_path: &PathBuf) -> Option<Self> {
         let content = match tokio::fs::read_to_string(file_path).await {
             Ok(c) => c,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                error!(
+                    "No config file found at '{}'. Run 'vampus init' to create one.",
+                    file_path.display()
+                );
+                return None;
+            }
             Err(e) => {
                 error!("Failed to read config file '{}': {}", file_path.display(), e);
                 return None;
@@ -77,12 +340,22 @@ impl Config {
         }
     }
 
-    pub async fn write(&self, file: &PathBuf){
-        match tokio::fs::write(
-                file,
-            serde_yaml::to_string(self).unwrap().as_bytes()).await{
-            Ok(_) => debug!("Successfully wrote config file to {}", file.display()),
-            Err(e) => error!("Failed to write config file: {}", e),
+    /// Serializa la configuración tal y como se escribe en `.vampus.yml`.
+    pub fn to_yaml(&self) -> Result<String, io::Error> {
+        serde_yaml::to_string(self).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    pub async fn write(&self, file: &Path) -> Result<(), io::Error> {
+        let content = self.to_yaml()?;
+        match apply_replacement(&file.to_string_lossy(), &content).await {
+            Ok(_) => {
+                debug!("Successfully wrote config file to {}", file.display());
+                Ok(())
+            }
+            Err(e) => {
+                error!("Failed to write config file: {}", e);
+                Err(e)
+            }
         }
     }
 }
@@ -0,0 +1,103 @@
+use regex::Regex;
+use std::sync::LazyLock;
+
+use crate::git::{self, Commit};
+
+// =============================================================================================
+// CONVENTIONAL COMMITS
+// =============================================================================================
+
+/// Commit del historial junto con el nivel de cambio que justifica (si justifica alguno).
+pub struct ClassifiedCommit {
+    pub commit: Commit,
+    pub change_type: Option<&'static str>,
+}
+
+/// Resultado del modo `--auto`: la etiqueta de referencia, los commits analizados y el
+/// tipo de cambio resultante.
+pub struct AutoChange {
+    pub tag: Option<String>,
+    pub commits: Vec<ClassifiedCommit>,
+    pub change_type: Option<&'static str>,
+}
+
+// Cabecera `tipo(ámbito)!: descripción`.
+static HEADER: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(?P<type>[A-Za-z]+)(?:\([^)]*\))?(?P<breaking>!)?:\s").unwrap());
+
+fn rank(change_type: Option<&str>) -> u8 {
+    match change_type {
+        Some("major") => 3,
+        Some("minor") => 2,
+        Some("patch") => 1,
+        _ => 0,
+    }
+}
+
+/// Clasifica un commit según Conventional Commits: `feat` -> minor, `fix`/`perf` -> patch
+/// y `!` o `BREAKING CHANGE` -> major (minor mientras la versión sea 0.x).
+pub fn classify(commit: &Commit, initial_development: bool) -> Option<&'static str> {
+    let captures = HEADER.captures(&commit.subject)?;
+
+    let breaking = captures.name("breaking").is_some()
+        || commit
+            .body
+            .lines()
+            .any(|line| line.starts_with("BREAKING CHANGE:") || line.starts_with("BREAKING-CHANGE:"));
+    if breaking {
+        return Some(if initial_development { "minor" } else { "major" });
+    }
+
+    match captures["type"].to_ascii_lowercase().as_str() {
+        "feat" => Some("minor"),
+        "fix" | "perf" => Some("patch"),
+        _ => None,
+    }
+}
+
+/// Lee los commits desde `tag` (la última etiqueta de versión) y deduce el tipo de cambio.
+pub async fn infer_version_change(tag: Option<String>, initial_development: bool) -> Result<AutoChange, String> {
+    let commits: Vec<ClassifiedCommit> = git::commits_since(tag.as_deref())
+        .await?
+        .into_iter()
+        .map(|commit| ClassifiedCommit {
+            change_type: classify(&commit, initial_development),
+            commit,
+        })
+        .collect();
+
+    let change_type = commits
+        .iter()
+        .map(|c| c.change_type)
+        .max_by_key(|change_type| rank(*change_type))
+        .flatten();
+
+    Ok(AutoChange {
+        tag,
+        commits,
+        change_type,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn commit(subject: &str, body: &str) -> Commit {
+        Commit {
+            sha: "0000000".to_string(),
+            subject: subject.to_string(),
+            body: body.to_string(),
+        }
+    }
+
+    #[test]
+    fn classifies_conventional_headers() {
+        assert_eq!(classify(&commit("feat(cli): add --auto", ""), false), Some("minor"));
+        assert_eq!(classify(&commit("fix: handle empty tags", ""), false), Some("patch"));
+        assert_eq!(classify(&commit("refactor!: drop old config", ""), false), Some("major"));
+        assert_eq!(classify(&commit("docs: typo", "BREAKING CHANGE: none"), true), Some("minor"));
+        assert_eq!(classify(&commit("chore: bump deps", ""), false), None);
+        assert_eq!(classify(&commit("Merge branch 'main'", ""), false), None);
+    }
+}
@@ -0,0 +1,51 @@
+use similar::TextDiff;
+
+// =============================================================================================
+// DIFF UNIFICADO
+// =============================================================================================
+
+const RED: &str = "\x1b[31m";
+const GREEN: &str = "\x1b[32m";
+const CYAN: &str = "\x1b[36m";
+const BOLD: &str = "\x1b[1m";
+const RESET: &str = "\x1b[0m";
+
+/// Genera el diff unificado entre `original` y `modified` con `context` líneas de contexto.
+/// Con `color` se usan secuencias ANSI (cabeceras en negrita, hunks en cian, líneas
+/// eliminadas en rojo y añadidas en verde).
+pub fn render_unified_diff(
+    path: &str,
+    original: &str,
+    modified: &str,
+    context: usize,
+    color: bool,
+) -> String {
+    let diff = TextDiff::from_lines(original, modified);
+    let unified = diff
+        .unified_diff()
+        .context_radius(context)
+        .header(&format!("a/{}", path), &format!("b/{}", path))
+        .to_string();
+
+    if !color {
+        return unified;
+    }
+
+    unified
+        .lines()
+        .map(|line| {
+            let style = if line.starts_with("+++") || line.starts_with("---") {
+                BOLD
+            } else if line.starts_with("@@") {
+                CYAN
+            } else if line.starts_with('+') {
+                GREEN
+            } else if line.starts_with('-') {
+                RED
+            } else {
+                return format!("{}\n", line);
+            };
+            format!("{}{}{}\n", style, line, RESET)
+        })
+        .collect()
+}
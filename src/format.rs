@@ -0,0 +1,170 @@
+use regex::Regex;
+use std::cmp::Ordering;
+
+use crate::utils::Operation;
+
+// =============================================================================================
+// FORMATOS DE VERSIÓN PERSONALIZADOS (parse / serialize)
+// =============================================================================================
+
+/// Formato de versión descrito por una RegEx con grupos con nombre (`parse`) y una lista
+/// ordenada de plantillas (`serialize`), al estilo de bump2version.
+///
+/// El orden de los grupos en `parse` define la significancia de cada parte: al incrementar
+/// una parte, todas las que aparecen después se reinician a 0.
+#[derive(Debug, Clone)]
+pub struct VersionFormat {
+    parse: Regex,
+    parts: Vec<String>,
+    serialize: Vec<String>,
+}
+
+impl VersionFormat {
+    pub fn new(parse: &str, serialize: &[String]) -> Result<Self, String> {
+        let regex = Regex::new(&format!("^(?:{})$", parse))
+            .map_err(|e| format!("Error compiling parse pattern '{}': {}", parse, e))?;
+        let parts: Vec<String> = regex
+            .capture_names()
+            .flatten()
+            .map(|name| name.to_string())
+            .collect();
+        if parts.is_empty() {
+            return Err(format!("Parse pattern '{}' has no named groups", parse));
+        }
+        if serialize.is_empty() {
+            return Err("At least one serialize template is required".to_string());
+        }
+        for template in serialize {
+            for name in template_parts(template) {
+                if !parts.contains(&name) {
+                    return Err(format!(
+                        "Serialize template '{}' uses '{}', which is not a part of the parse pattern",
+                        template, name
+                    ));
+                }
+            }
+        }
+
+        Ok(Self {
+            parse: regex,
+            parts,
+            serialize: serialize.to_vec(),
+        })
+    }
+
+    /// Devuelve el valor de cada parte en orden de significancia. Las partes opcionales
+    /// que no aparecen en la versión valen 0.
+    fn values(&self, version: &str) -> Result<Vec<u64>, String> {
+        let captures = self.parse.captures(version.trim()).ok_or_else(|| {
+            format!("Version '{}' does not match the parse pattern '{}'", version, self.parse.as_str())
+        })?;
+        self.parts
+            .iter()
+            .map(|name| match captures.name(name) {
+                Some(m) => m.as_str().parse::<u64>().map_err(|_| {
+                    format!("Part '{}' of version '{}' is not a number: '{}'", name, version, m.as_str())
+                }),
+                None => Ok(0),
+            })
+            .collect()
+    }
+
+    /// Usa la última plantilla de `serialize` capaz de representar todas las partes
+    /// distintas de 0 (como bump2version), o la primera plantilla si ninguna lo consigue.
+    fn serialize(&self, values: &[u64]) -> String {
+        let template = self
+            .serialize
+            .iter()
+            .rfind(|template| {
+                let used = template_parts(template);
+                self.parts
+                    .iter()
+                    .zip(values)
+                    .all(|(name, value)| *value == 0 || used.contains(name))
+            })
+            .unwrap_or(&self.serialize[0]);
+
+        self.parts
+            .iter()
+            .zip(values)
+            .fold(template.clone(), |acc, (name, value)| {
+                acc.replace(&format!("{{{{{}}}}}", name), &value.to_string())
+            })
+    }
+
+    /// Devuelve el nombre y el valor de cada parte de `version`.
+    pub fn parts(&self, version: &str) -> Result<Vec<(String, u64)>, String> {
+        Ok(self.parts.iter().cloned().zip(self.values(version)?).collect())
+    }
+
+    /// Comprueba que `version` sigue el formato y la devuelve serializada.
+    pub fn validate(&self, version: &str) -> Result<String, String> {
+        Ok(self.serialize(&self.values(version)?))
+    }
+
+    /// Compara dos versiones parte a parte en orden de significancia.
+    pub fn compare(&self, a: &str, b: &str) -> Result<Ordering, String> {
+        Ok(self.values(a)?.cmp(&self.values(b)?))
+    }
+
+    /// Incrementa o decrementa la parte `part`, reiniciando a 0 las partes dependientes.
+    pub fn bump(&self, version: &str, part: &str, operation: Operation) -> Result<String, String> {
+        let mut values = self.values(version)?;
+        let index = self.parts.iter().position(|name| name == part).ok_or_else(|| {
+            format!(
+                "Unknown part '{}'. Available parts: {}",
+                part,
+                self.parts.join(", ")
+            )
+        })?;
+
+        match operation {
+            Operation::Increment => values[index] += 1,
+            Operation::Decrement => {
+                if values[index] == 0 {
+                    return Err(format!("Cannot downgrade part '{}' of version {}: it is already 0", part, version));
+                }
+                values[index] -= 1;
+            }
+        }
+        values.iter_mut().skip(index + 1).for_each(|value| *value = 0);
+
+        Ok(self.serialize(&values))
+    }
+}
+
+/// Extrae los nombres de las partes usadas en una plantilla (`{{major}}.{{minor}}`).
+fn template_parts(template: &str) -> Vec<String> {
+    let re = Regex::new(r"\{\{(\w+)\}\}").unwrap();
+    re.captures_iter(template)
+        .map(|c| c[1].to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn format(parse: &str, serialize: &[&str]) -> VersionFormat {
+        let serialize: Vec<String> = serialize.iter().map(|template| template.to_string()).collect();
+        VersionFormat::new(parse, &serialize).unwrap()
+    }
+
+    #[test]
+    fn serialize_drops_zero_dev_part() {
+        let format = format(
+            r"(?P<major>\d+)\.(?P<minor>\d+)(?:-dev(?P<dev>\d+))?",
+            &["{{major}}.{{minor}}-dev{{dev}}", "{{major}}.{{minor}}"],
+        );
+        assert_eq!(format.bump("1.2-dev3", "minor", Operation::Increment).unwrap(), "1.3");
+        assert_eq!(format.bump("1.2-dev3", "dev", Operation::Increment).unwrap(), "1.2-dev4");
+        assert_eq!(format.validate("1.2-dev3").unwrap(), "1.2-dev3");
+    }
+
+    #[test]
+    fn serialize_uses_last_template_that_fits() {
+        let format = format(r"(?P<major>\d+)(?:\.(?P<minor>\d+))?", &["{{major}}.{{minor}}", "{{major}}"]);
+        assert_eq!(format.bump("1.4", "major", Operation::Increment).unwrap(), "2");
+        assert_eq!(format.bump("2", "minor", Operation::Increment).unwrap(), "2.1");
+    }
+}
@@ -0,0 +1,131 @@
+use tokio::process::Command;
+use tracing::debug;
+
+// =============================================================================================
+// INTEGRACIÓN CON GIT
+// =============================================================================================
+
+/// Commit leído del historial local.
+#[derive(Debug, Clone)]
+pub struct Commit {
+    pub sha: String,
+    pub subject: String,
+    pub body: String,
+}
+
+impl Commit {
+    pub fn short_sha(&self) -> &str {
+        &self.sha[..self.sha.len().min(7)]
+    }
+}
+
+/// Ejecuta `git` con los argumentos dados y devuelve su salida estándar.
+pub async fn run(args: &[&str]) -> Result<String, String> {
+    debug!("Running: git {}", args.join(" "));
+    let output = Command::new("git")
+        .args(args)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run git: {}", e))?;
+
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    } else {
+        Err(format!(
+            "'git {}' failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
+
+/// Indica si el directorio actual está dentro de un repositorio git.
+pub async fn is_repository() -> bool {
+    run(&["rev-parse", "--is-inside-work-tree"])
+        .await
+        .is_ok_and(|output| output.trim() == "true")
+}
+
+/// Devuelve el nombre de la rama actual (`HEAD` si está desacoplada).
+pub async fn current_branch() -> Result<String, String> {
+    run(&["rev-parse", "--abbrev-ref", "HEAD"])
+        .await
+        .map(|branch| branch.trim().to_string())
+}
+
+/// Devuelve el SHA corto del commit actual.
+pub async fn short_sha() -> Result<String, String> {
+    run(&["rev-parse", "--short", "HEAD"])
+        .await
+        .map(|sha| sha.trim().to_string())
+}
+
+/// Devuelve los archivos versionados con cambios sin confirmar (preparados o no),
+/// con rutas relativas a la raíz del repositorio.
+pub async fn uncommitted_files() -> Result<Vec<String>, String> {
+    let status = run(&["status", "--porcelain", "--untracked-files=no"]).await?;
+    Ok(status
+        .lines()
+        .filter(|line| line.len() > 3)
+        .map(|line| {
+            let path = &line[3..];
+            // En los renombrados solo interesa la ruta de destino.
+            path.rsplit(" -> ").next().unwrap_or(path).trim_matches('"').to_string()
+        })
+        .collect())
+}
+
+/// Devuelve las etiquetas que empiezan por `prefix` alcanzables desde HEAD.
+pub async fn merged_tags(prefix: &str) -> Result<Vec<String>, String> {
+    let pattern = format!("{}*", prefix);
+    let tags = run(&["tag", "--merged", "HEAD", "--list", &pattern]).await?;
+    Ok(tags
+        .lines()
+        .map(str::trim)
+        .filter(|tag| !tag.is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
+/// Lee los commits desde `tag` (exclusivo) hasta HEAD, o todo el historial si no hay etiqueta.
+pub async fn commits_since(tag: Option<&str>) -> Result<Vec<Commit>, String> {
+    let range = match tag {
+        Some(tag) => format!("{}..HEAD", tag),
+        None => "HEAD".to_string(),
+    };
+    // Campos separados por US (0x1f) y commits por RS (0x1e).
+    let log = run(&["log", "--format=%H%x1f%s%x1f%b%x1e", &range]).await?;
+
+    Ok(log
+        .split('\x1e')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .filter_map(|entry| {
+            let mut fields = entry.splitn(3, '\x1f');
+            Some(Commit {
+                sha: fields.next()?.to_string(),
+                subject: fields.next()?.to_string(),
+                body: fields.next().unwrap_or_default().trim().to_string(),
+            })
+        })
+        .collect())
+}
+
+/// Añade `paths` al índice y crea un commit que incluye únicamente esos archivos,
+/// aunque haya otros cambios preparados.
+pub async fn commit_files(paths: &[String], message: &str) -> Result<(), String> {
+    let mut add = vec!["add", "--"];
+    add.extend(paths.iter().map(String::as_str));
+    run(&add).await?;
+
+    let mut commit = vec!["commit", "-m", message, "--"];
+    commit.extend(paths.iter().map(String::as_str));
+    run(&commit).await?;
+    Ok(())
+}
+
+/// Crea una etiqueta anotada sobre HEAD.
+pub async fn create_tag(name: &str, message: &str) -> Result<(), String> {
+    run(&["tag", "-a", name, "-m", message]).await?;
+    Ok(())
+}
@@ -0,0 +1,319 @@
+use regex::Regex;
+use std::{collections::HashMap, path::Path, sync::LazyLock};
+use tokio::fs;
+use toml_edit::{DocumentMut, Item};
+
+use crate::config::{Config, FileSpec, Replace, Scheme};
+use crate::pep440::Pep440Version;
+use crate::version::Version;
+
+// =============================================================================================
+// INIT: DETECCIÓN DE ECOSISTEMAS
+// =============================================================================================
+
+/// Manifiesto detectado: la versión que contiene y la regla que la mantiene.
+pub struct DetectedFile {
+    pub file: String,
+    pub version: String,
+    pub rule: Replace,
+}
+
+/// Configuración propuesta por `init`, junto con los archivos descartados por tener una
+/// versión distinta de la elegida.
+pub struct Proposal {
+    pub config: Config,
+    pub detected: Vec<DetectedFile>,
+    pub skipped: Vec<DetectedFile>,
+}
+
+const LOCKFILES: [&str; 3] = ["package-lock.json", "Cargo.lock", "uv.lock"];
+
+// Líneas con la versión en archivos sin formato estructurado (grupo `version`).
+static GRADLE_VERSION: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"^\s*version\s*=?\s*["'](?P<version>[^"']+)["']\s*$"#).unwrap());
+static GRADLE_KTS_VERSION: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"^\s*version\s*=\s*"(?P<version>[^"]+)"\s*$"#).unwrap());
+static DOCKERFILE_VERSION: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"^\s*LABEL\s+.*\b(?:org\.opencontainers\.image\.)?version="?(?P<version>[0-9][^"\s]*)"?"#).unwrap()
+});
+static SETUP_CFG_VERSION: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(?P<prefix>version\s*=\s*)(?P<version>[0-9][^\s#;]*)\s*$").unwrap());
+
+fn rule(file: &str) -> Replace {
+    Replace {
+        file: FileSpec::Single(file.to_string()),
+        ..Replace::default()
+    }
+}
+
+async fn read(file: &str) -> Option<String> {
+    fs::read_to_string(file).await.ok()
+}
+
+fn toml_string(document: &DocumentMut, path: &[&str]) -> Option<String> {
+    path.iter()
+        .try_fold(document.as_item(), |item, key| item.get(key))
+        .and_then(Item::as_str)
+        .map(str::to_string)
+}
+
+// Primera clave TOML de `paths` que tiene una versión.
+async fn detect_toml(file: &str, paths: &[&[&str]]) -> Option<DetectedFile> {
+    let document = read(file).await?.parse::<DocumentMut>().ok()?;
+    paths.iter().find_map(|path| {
+        toml_string(&document, path).map(|version| DetectedFile {
+            file: file.to_string(),
+            version,
+            rule: Replace {
+                toml_path: Some(path.join(".")),
+                ..rule(file)
+            },
+        })
+    })
+}
+
+async fn detect_json(file: &str) -> Option<DetectedFile> {
+    let document: serde_json::Value = serde_json::from_str(&read(file).await?).ok()?;
+    Some(DetectedFile {
+        file: file.to_string(),
+        version: document.get("version")?.as_str()?.to_string(),
+        rule: Replace {
+            json_path: Some("version".to_string()),
+            ..rule(file)
+        },
+    })
+}
+
+async fn detect_yaml(file: &str) -> Option<DetectedFile> {
+    let document: serde_yaml::Value = serde_yaml::from_str(&read(file).await?).ok()?;
+    let version = match document.get("version")? {
+        serde_yaml::Value::String(version) => version.clone(),
+        serde_yaml::Value::Number(version) => version.to_string(),
+        _ => return None,
+    };
+    Some(DetectedFile {
+        file: file.to_string(),
+        version,
+        rule: Replace {
+            yaml_path: Some("version".to_string()),
+            ..rule(file)
+        },
+    })
+}
+
+// Texto del primer elemento que sigue `path` desde la raíz (nombres locales).
+async fn detect_xml(file: &str, path: &[&str]) -> Option<DetectedFile> {
+    let content = read(file).await?;
+    let document = roxmltree::Document::parse(&content).ok()?;
+    let root = document.root_element();
+    let (first, rest) = path.split_first()?;
+    if root.tag_name().name() != *first {
+        return None;
+    }
+    let element = rest.iter().try_fold(root, |node, name| {
+        node.children()
+            .find(|child| child.is_element() && child.tag_name().name() == *name)
+    })?;
+    let version = element.text()?.trim().to_string();
+    (!version.is_empty()).then(|| DetectedFile {
+        file: file.to_string(),
+        version,
+        rule: Replace {
+            xml_path: Some(format!("/{}", path.join("/"))),
+            ..rule(file)
+        },
+    })
+}
+
+// `.csproj`: `<Version>` dentro de cualquier `PropertyGroup`.
+async fn detect_csproj(file: &str) -> Option<DetectedFile> {
+    let content = read(file).await?;
+    let document = roxmltree::Document::parse(&content).ok()?;
+    let version = document
+        .descendants()
+        .find(|node| {
+            node.tag_name().name() == "Version"
+                && node.parent().is_some_and(|parent| parent.tag_name().name() == "PropertyGroup")
+        })?
+        .text()?
+        .trim()
+        .to_string();
+    Some(DetectedFile {
+        file: file.to_string(),
+        version,
+        rule: Replace {
+            xml_path: Some("PropertyGroup/Version".to_string()),
+            ..rule(file)
+        },
+    })
+}
+
+/// Busca la primera línea que coincide con `line_pattern` (con un grupo `version`) y
+/// propone un patrón anclado a esa línea exacta.
+async fn detect_line(file: &str, line_pattern: &Regex) -> Option<DetectedFile> {
+    let content = read(file).await?;
+    content.lines().find_map(|line| {
+        let version = line_pattern.captures(line)?.name("version")?;
+        let pattern = format!(
+            "^{}{{{{current_version}}}}{}$",
+            regex::escape(&line[..version.start()]),
+            regex::escape(&line[version.end()..])
+        );
+        Some(DetectedFile {
+            file: file.to_string(),
+            version: version.as_str().to_string(),
+            rule: Replace {
+                pattern,
+                count: Some(1),
+                ..rule(file)
+            },
+        })
+    })
+}
+
+// `setup.cfg`: `version = X` dentro de la sección `[metadata]`.
+async fn detect_setup_cfg(file: &str) -> Option<DetectedFile> {
+    let content = read(file).await?;
+    let metadata: String = content
+        .split_inclusive('\n')
+        .skip_while(|line| line.trim() != "[metadata]")
+        .skip(1)
+        .take_while(|line| !line.trim_start().starts_with('['))
+        .collect();
+    metadata.lines().find_map(|line| {
+        let captures = SETUP_CFG_VERSION.captures(line)?;
+        Some(DetectedFile {
+            file: file.to_string(),
+            version: captures["version"].to_string(),
+            rule: Replace {
+                pattern: format!("^{}{{{{current_version}}}}$", regex::escape(&captures["prefix"])),
+                count: Some(1),
+                ..rule(file)
+            },
+        })
+    })
+}
+
+async fn files_with_extension(extension: &str) -> Vec<String> {
+    let mut files = Vec::new();
+    if let Ok(mut entries) = fs::read_dir(".").await {
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if Path::new(&name).extension().is_some_and(|ext| ext == extension) {
+                files.push(name);
+            }
+        }
+    }
+    files.sort();
+    files
+}
+
+// Un `Cargo.toml` con `workspace.members` se gestiona con la integración de workspaces.
+async fn is_cargo_workspace() -> bool {
+    read("Cargo.toml")
+        .await
+        .and_then(|content| content.parse::<DocumentMut>().ok())
+        .and_then(|document| document.get("workspace")?.get("members").map(|_| ()))
+        .is_some()
+}
+
+/// Detecta los manifiestos conocidos en el directorio actual, en orden de prioridad.
+pub async fn detect_files() -> Vec<DetectedFile> {
+    let mut detected: Vec<Option<DetectedFile>> = vec![
+        detect_toml("Cargo.toml", &[&["package", "version"], &["workspace", "package", "version"]]).await,
+        detect_json("package.json").await,
+        detect_toml("pyproject.toml", &[&["project", "version"], &["tool", "poetry", "version"]]).await,
+        detect_setup_cfg("setup.cfg").await,
+        detect_yaml("Chart.yaml").await,
+        detect_xml("pom.xml", &["project", "version"]).await,
+        detect_line("build.gradle", &GRADLE_VERSION).await,
+        detect_line("build.gradle.kts", &GRADLE_KTS_VERSION).await,
+    ];
+    for file in files_with_extension("csproj").await {
+        detected.push(detect_csproj(&file).await);
+    }
+    detected.push(detect_line("Dockerfile", &DOCKERFILE_VERSION).await);
+    detected.into_iter().flatten().collect()
+}
+
+/// Esquema con el que se puede gestionar `version`. Las versiones CalVer habituales
+/// (`2024.10.1`, `24.01.0`) ya son SemVer o PEP 440 válidas; si no encaja en ninguno, no se
+/// adivina un esquema.
+fn detect_scheme(version: &str) -> Option<Scheme> {
+    if version.parse::<Version>().is_ok() {
+        Some(Scheme::SemVer)
+    } else if version.parse::<Pep440Version>().is_ok() {
+        Some(Scheme::Pep440)
+    } else {
+        None
+    }
+}
+
+/// Propone una configuración a partir de los manifiestos detectados. La versión actual es
+/// la más repetida (en caso de empate, la del manifiesto más prioritario). Devuelve `None`
+/// si no se detecta ningún manifiesto y un error si la versión no sigue ningún esquema.
+pub async fn propose_config() -> Result<Option<Proposal>, String> {
+    let detected = detect_files().await;
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for file in &detected {
+        *counts.entry(file.version.as_str()).or_default() += 1;
+    }
+    let Some(version) = detected
+        .iter()
+        .max_by_key(|file| {
+            let position = detected.iter().position(|f| f.version == file.version).unwrap_or(0);
+            (counts[file.version.as_str()], usize::MAX - position)
+        })
+        .map(|file| file.version.clone())
+    else {
+        return Ok(None);
+    };
+    let scheme = detect_scheme(&version).ok_or_else(|| {
+        format!(
+            "Detected version '{}' is not valid SemVer or PEP 440. Write the config file by hand and set its scheme.",
+            version
+        )
+    })?;
+
+    let (detected, skipped): (Vec<DetectedFile>, Vec<DetectedFile>) =
+        detected.into_iter().partition(|file| file.version == version);
+
+    let mut config = Config::new(version.clone());
+    config.scheme = scheme;
+    config.cargo_workspace = is_cargo_workspace().await;
+    config.sync_lockfiles = LOCKFILES.iter().any(|lockfile| Path::new(lockfile).is_file());
+    config.replaces = detected.iter().map(|file| file.rule.clone()).collect();
+
+    Ok(Some(Proposal {
+        config,
+        detected,
+        skipped,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn line_patterns_capture_the_version() {
+        let cases: [(&Regex, &str); 4] = [
+            (&GRADLE_VERSION, "version = '1.2.3'"),
+            (&GRADLE_KTS_VERSION, "version = \"1.2.3\""),
+            (&DOCKERFILE_VERSION, "LABEL org.opencontainers.image.version=\"1.2.3\""),
+            (&SETUP_CFG_VERSION, "version = 1.2.3"),
+        ];
+        for (pattern, line) in cases {
+            assert_eq!(&pattern.captures(line).unwrap()["version"], "1.2.3", "{}", line);
+        }
+    }
+
+    #[test]
+    fn scheme_is_only_proposed_for_parseable_versions() {
+        assert_eq!(detect_scheme("1.2.3-rc.1"), Some(Scheme::SemVer));
+        assert_eq!(detect_scheme("2024.10.1"), Some(Scheme::SemVer));
+        assert_eq!(detect_scheme("1.2rc1"), Some(Scheme::Pep440));
+        assert_eq!(detect_scheme("24.01.0"), Some(Scheme::Pep440));
+        assert_eq!(detect_scheme("release-7"), None);
+    }
+}
@@ -0,0 +1,180 @@
+use std::{collections::HashMap, io, path::Path};
+use toml_edit::{DocumentMut, Item};
+
+use crate::cargo::{bump_lockfile_packages, bump_string, load};
+use crate::structured::{invalid_data, json_value_span};
+use crate::utils::SimulatedFile;
+
+// =============================================================================================
+// SINCRONIZACIÓN DE LOCKFILES
+// =============================================================================================
+
+/// Formatos de lockfile soportados: el lockfile y el manifiesto del que sale el nombre del
+/// paquete propio del proyecto. `poetry.lock` no se incluye porque solo contiene las
+/// dependencias, nunca una entrada del propio proyecto.
+#[derive(Debug, Clone, Copy)]
+enum Lockfile {
+    Npm,
+    Cargo,
+    Uv,
+}
+
+impl Lockfile {
+    const ALL: [Lockfile; 3] = [Lockfile::Npm, Lockfile::Cargo, Lockfile::Uv];
+
+    fn path(self) -> &'static str {
+        match self {
+            Lockfile::Npm => "package-lock.json",
+            Lockfile::Cargo => "Cargo.lock",
+            Lockfile::Uv => "uv.lock",
+        }
+    }
+}
+
+fn parse_toml(path: &str, content: &str) -> Result<DocumentMut, io::Error> {
+    content
+        .parse::<DocumentMut>()
+        .map_err(|e| invalid_data(format!("File '{}' is not valid TOML: {}", path, e)))
+}
+
+// Nombre normalizado según PEP 503 (`My_Package` -> `my-package`).
+fn normalize_python_name(name: &str) -> String {
+    name.to_ascii_lowercase().replace(['_', '.'], "-")
+}
+
+/// `package-lock.json`: la versión de la raíz (`version`) y la de `packages[""]`.
+fn sync_npm(content: &str, current_version: &str, new_version: &str) -> Result<(String, usize), String> {
+    let mut spans = Vec::new();
+    for segments in [&["version"][..], &["packages", "", "version"][..]] {
+        if let Some(span) = json_value_span(content, segments)?
+            && serde_json::from_str::<String>(&content[span.0..span.1]).ok().as_deref() == Some(current_version)
+        {
+            spans.push(span);
+        }
+    }
+
+    // Se sustituye de atrás hacia delante para no desplazar los rangos pendientes.
+    spans.sort_by_key(|span| std::cmp::Reverse(span.0));
+    let new_raw = serde_json::to_string(new_version).map_err(|e| e.to_string())?;
+    let mut modified = content.to_string();
+    for (start, end) in &spans {
+        modified.replace_range(start..end, &new_raw);
+    }
+    serde_json::from_str::<serde_json::Value>(&modified).map_err(|e| e.to_string())?;
+    Ok((modified, spans.len()))
+}
+
+/// `uv.lock`: la entrada `[[package]]` del propio proyecto, que es la que tiene un origen
+/// local (`source = { editable = "." }`, `virtual` o `directory`).
+fn sync_uv(
+    document: &mut DocumentMut,
+    name: &str,
+    current_version: &str,
+    new_version: &str,
+) -> usize {
+    let Some(packages) = document.get_mut("package").and_then(Item::as_array_of_tables_mut) else {
+        return 0;
+    };
+    let name = normalize_python_name(name);
+    let mut changes = 0;
+    for package in packages.iter_mut() {
+        let is_project = package
+            .get("name")
+            .and_then(Item::as_str)
+            .is_some_and(|n| normalize_python_name(n) == name);
+        let is_local = package.get("source").and_then(Item::as_table_like).is_some_and(|source| {
+            ["editable", "virtual", "directory"].iter().any(|key| source.contains_key(key))
+        });
+        if !is_project || !is_local {
+            continue;
+        }
+        if let Some(version) = package.get_mut("version").and_then(Item::as_value_mut)
+            && bump_string(version, current_version, new_version)
+        {
+            changes += 1;
+        }
+    }
+    changes
+}
+
+// Nombre del paquete propio según el manifiesto (`Cargo.toml` o `pyproject.toml`).
+async fn manifest_name(
+    manifest: &str,
+    keys: &[&[&str]],
+    contents: &HashMap<String, String>,
+) -> Result<Option<String>, io::Error> {
+    if !Path::new(manifest).is_file() {
+        return Ok(None);
+    }
+    let document = parse_toml(manifest, &load(manifest, contents).await?)?;
+    Ok(keys.iter().find_map(|path| {
+        path.iter()
+            .try_fold(document.as_item(), |item, key| item.get(key))
+            .and_then(Item::as_str)
+            .map(str::to_string)
+    }))
+}
+
+async fn sync_lockfile(
+    lockfile: Lockfile,
+    content: &str,
+    contents: &HashMap<String, String>,
+    current_version: &str,
+    new_version: &str,
+) -> Result<(String, usize), io::Error> {
+    let path = lockfile.path();
+    match lockfile {
+        Lockfile::Npm => sync_npm(content, current_version, new_version)
+            .map_err(|e| invalid_data(format!("Error updating '{}': {}", path, e))),
+        Lockfile::Cargo => {
+            let Some(name) = manifest_name("Cargo.toml", &[&["package", "name"]], contents).await? else {
+                return Ok((content.to_string(), 0));
+            };
+            let mut document = parse_toml(path, content)?;
+            let changes = bump_lockfile_packages(&mut document, &[name], current_version, new_version);
+            Ok((document.to_string(), changes))
+        }
+        Lockfile::Uv => {
+            let Some(name) = manifest_name("pyproject.toml", &[&["project", "name"]], contents).await? else {
+                return Ok((content.to_string(), 0));
+            };
+            let mut document = parse_toml(path, content)?;
+            let changes = sync_uv(&mut document, &name, current_version, new_version);
+            Ok((document.to_string(), changes))
+        }
+    }
+}
+
+/// Actualiza la entrada del propio proyecto en cada lockfile presente. Solo se devuelven
+/// los lockfiles que cambian; el contenido resultante se valida antes de aceptarlo.
+pub async fn simulate_lockfiles(
+    contents: &HashMap<String, String>,
+    current_version: &str,
+    new_version: &str,
+) -> Vec<(String, Result<SimulatedFile, io::Error>)> {
+    let mut results = Vec::new();
+
+    for lockfile in Lockfile::ALL {
+        let path = lockfile.path();
+        if !Path::new(path).is_file() {
+            continue;
+        }
+        let result = match load(path, contents).await {
+            Ok(content) => sync_lockfile(lockfile, &content, contents, current_version, new_version)
+                .await
+                .map(|(modified, changes)| SimulatedFile {
+                    path: path.to_string(),
+                    original: content,
+                    modified,
+                    matches: changes,
+                }),
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(simulated) if simulated.matches == 0 => {}
+            result => results.push((path.to_string(), result)),
+        }
+    }
+
+    results
+}
@@ -1,20 +1,55 @@
-use std::{env, str::FromStr};
+use chrono::Local;
+use std::{collections::HashMap, env, io::{self, IsTerminal}, path::{Path, PathBuf}, str::FromStr};
 use tracing::{debug, error};
-use tracing_subscriber::{EnvFilter, layer::SubscriberExt, util::SubscriberInitExt};
+use tracing_subscriber::{EnvFilter, fmt::writer::BoxMakeWriter, layer::SubscriberExt, util::SubscriberInitExt};
 use clap::Parser;
 
+mod calver;
+mod cargo;
+mod check;
 mod cli;
 mod config;
+mod conventional;
+mod diff;
+mod format;
+mod git;
+mod init;
+mod lockfile;
+mod pep440;
+mod report;
+mod structured;
+mod template;
 mod utils;
-use cli::{Cli, Commands}; // Asegúrate de importar VersionArgs
-use config::Config;
+mod version;
+use cargo::simulate_cargo_workspace;
+use check::{CheckStatus, check_files};
+use cli::{Cli, Commands, DiffArgs, OutputFormat}; // Asegúrate de importar VersionArgs
+use config::{Config, Replace, VersionSource};
+use conventional::AutoChange;
+use diff::render_unified_diff;
+use init::propose_config;
+use lockfile::simulate_lockfiles;
+use report::Report;
+use structured::simulate_structured_replacement;
+use template::TemplateContext;
 use utils::{
     apply_replacement,
     simulate_replacement,
     get_config_path,
-    calculate_version,
-    Operation,
-    get_version_change,
+    calculate_next_version,
+    calculate_previous_version,
+    calculate_target_version,
+    expand_replace_files,
+    get_pre_release_change,
+    merge_simulated_files,
+    read_text_file,
+    resolve_current_version,
+    rollback_replacements,
+    SimulatedFile,
+    resolve_version_change,
+    run_preflight_checks,
+    compare_versions,
+    validate_version,
     wrap_search_pattern,
 };
 
@@ -25,18 +60,24 @@ use utils::{
 #[tokio::main]
 async fn main() {
     let cli = Cli::parse();
-    
+
     let log_filter_str = if cli.debug {
         "debug".to_string()
     } else {
         env::var("RUST_LOG").unwrap_or("ERROR".to_string())
     };
 
+    // En JSON, stdout queda reservado para el documento y los logs van a stderr.
+    let log_writer = match cli.output {
+        OutputFormat::Human => BoxMakeWriter::new(io::stdout),
+        OutputFormat::Json => BoxMakeWriter::new(io::stderr),
+    };
+
     // Inicialización del subscriber UNA SOLA VEZ
     tracing_subscriber::registry()
         .with(EnvFilter::from_str(&log_filter_str)
-            .unwrap_or_else(|_| EnvFilter::from_str("error").unwrap())) 
-        .with(tracing_subscriber::fmt::layer())
+            .unwrap_or_else(|_| EnvFilter::from_str("error").unwrap()))
+        .with(tracing_subscriber::fmt::layer().with_writer(log_writer))
         .init();
 
     debug!("log_level: {}", log_filter_str);
@@ -45,264 +86,934 @@ async fn main() {
         debug!("DEBUG mode enabled via CLI flag.");
     }
 
+    let mut report = Report::new(cli.output, cli.command.name());
+
     match &cli.command {
         // -------------------------------------------------------------------------------------
         // COMANDO UPGRADE
         // -------------------------------------------------------------------------------------
         Commands::Upgrade(args) => {
-            let (change_type, _) = get_version_change(args);
             let config_path = get_config_path().await;
+            let Some(mut config) = load_config(&config_path).await else {
+                config_error(report, &config_path);
+            };
+            let current_version = match transaction_current_version(&config) {
+                Ok(version) => version,
+                Err(e) => {
+                    report.fail("invalid_version", format!("Invalid current version in config: {}", e));
+                    report.finish();
+                }
+            };
+            report.current_version = Some(current_version.clone());
 
-            match Config::read(&config_path).await {
-                Some(mut config) => {
-                    match calculate_version(
-                        &config.current_version,
-                        change_type,
-                        Operation::Increment,
-                    ) {
-                        Ok(new_version) => {
-                            println!("Current version: {}", config.current_version);
-                            println!("New version (preview): {}", new_version);
-                            
-                            // El string de reemplazo usa los grupos de captura $1 y $2.
-                            let replacement_to = format!("${{1}}{}${{2}}", new_version);
-                            debug!("Replacement TO string: {}", replacement_to);
-
-                            let mut modified_files = Vec::new();
-                            let mut all_files_verified = true;
-
-                            // FASE 1: VERIFICACIÓN Y SIMULACIÓN
-                            println!("-- Verifying and simulating changes... --");
-
-                            for replace in &config.replaces {
-                                
-                                // APLICAR LÓGICA DE ENVOLTURA AUTOMÁTICA
-                                let wrapped_search = wrap_search_pattern(replace.pattern.as_str());
-                                debug!("Wrapped search pattern: {}", wrapped_search);
-                                
-                                // El patrón de búsqueda (FROM) usa la versión actual
-                                let pattern_from = format!(
-                                    "(?m){}",
-                                    wrapped_search.replace(
-                                        "{{current_version}}",
-                                        &config.current_version.replace(".", "\\."))
-                                );
-                                debug!("Pattern FROM: {}", pattern_from);
-
-                                // El patrón de verificación (TO) usa la nueva versión
-                                let pattern_to = format!(
-                                    "(?m){}",
-                                    wrapped_search.replace(
-                                        "{{current_version}}",
-                                        &new_version.replace(".", "\\."))
-                                );
-                                debug!("Pattern TO: {}", pattern_to);
-
-                                debug!(
-                                    "Simulating file: {} | FROM: {} | TO (Verif): {}",
-                                    replace.file, pattern_from, pattern_to
-                                );
-
-                                match simulate_replacement(
-                                    replace.file.as_str(),
-                                    &pattern_from,
-                                    &replacement_to, 
-                                    &pattern_to,
-                                )
-                                .await
-                                {
-                                    Ok(content) => {
-                                        modified_files.push((replace.file.clone(), content));
-                                    }
-                                    Err(e) => {
-                                        error!(File=%replace.file, "CRITICAL SIMULATION FAILURE: {}", e);
-                                        all_files_verified = false;
-                                        break;
-                                    }
-                                }
-                            }
-
-                            // FASE 2: EJECUCIÓN (sin cambios)
-                            if all_files_verified {
-                                println!("-- Applying changes... --");
-
-                                for (file_path, content) in modified_files {
-                                    match apply_replacement(file_path.as_str(), &content).await {
-                                        Ok(_) => {
-                                            println!("✅ Updated: {}", file_path);
-                                        }
-                                        Err(e) => {
-                                            error!(File=%file_path, "CRITICAL WRITE FAILURE: {}", e);
-                                        }
-                                    }
-                                }
-
-                                config.current_version = new_version;
-                                config.write(&config_path).await;
-                                println!(
-                                    "\n🎉 Success: Config version updated to {}",
-                                    config.current_version
-                                );
-                            } else {
-                                error!(
-                                    "Upgrade aborted. No changes were written to files."
-                                );
-                            }
-                        }
-                        Err(e) => {
-                            error!("Error calculating the version: {}", e);
-                        }
-                    }
+            let (change_type, auto_change) = match resolve_version_change(&config, &args.version).await {
+                Ok(resolved) => resolved,
+                Err(e) => {
+                    report.fail("version_inference_failed", format!("Error inferring the version change: {}", e));
+                    report.finish();
                 }
-                None => error!("Failed to read config file at {}", config_path.display()),
+            };
+            if let Some(auto_change) = &auto_change {
+                report_auto_change(&mut report, auto_change, false);
+                if change_type.is_none() {
+                    report.fail(
+                        "nothing_to_release",
+                        "No feat, fix or breaking change commits found. Nothing to release.",
+                    );
+                    report.finish();
+                }
+            }
+            let new_version = match calculate_next_version(
+                &config,
+                change_type,
+                get_pre_release_change(&args.pre_release),
+                Local::now().date_naive(),
+            ) {
+                Ok(version) => version,
+                Err(e) => {
+                    report.fail("version_calculation_failed", format!("Error calculating the version: {}", e));
+                    report.finish();
+                }
+            };
+
+            report.print(format_args!("Current version: {}", current_version));
+            report.print(format_args!("New version (preview): {}", new_version));
+            report.new_version = Some(new_version.clone());
+
+            if let Some(updated_files) = apply_version_change(
+                &mut report,
+                &mut config,
+                &config_path,
+                &current_version,
+                &new_version,
+                "Upgrade",
+                &ChangeOptions::new(cli.allow_dirty, &args.diff),
+            )
+            .await
+            {
+                commit_version_change(&mut report, &config, &config_path, &current_version, updated_files).await;
             }
         }
         // -------------------------------------------------------------------------------------
         // COMANDO DOWNGRADE
         // -------------------------------------------------------------------------------------
         Commands::Downgrade(args) => {
-            let (change_type, _) = get_version_change(args);
             let config_path = get_config_path().await;
+            let Some(mut config) = load_config(&config_path).await else {
+                config_error(report, &config_path);
+            };
+            let current_version = match transaction_current_version(&config) {
+                Ok(version) => version,
+                Err(e) => {
+                    report.fail("invalid_version", format!("Invalid current version in config: {}", e));
+                    report.finish();
+                }
+            };
+            report.current_version = Some(current_version.clone());
+
+            let target_version = match calculate_previous_version(&config, &args.version) {
+                Ok(version) => version,
+                Err(e) => {
+                    report.fail(
+                        "version_calculation_failed",
+                        format!("Error calculating the downgrade version: {}", e),
+                    );
+                    report.finish();
+                }
+            };
+
+            report.print(format_args!("Current version: {}", current_version));
+            report.print(format_args!("Target downgrade version (preview): {}", target_version));
+            report.new_version = Some(target_version.clone());
+
+            apply_version_change(
+                &mut report,
+                &mut config,
+                &config_path,
+                &current_version,
+                &target_version,
+                "Downgrade",
+                &ChangeOptions::new(cli.allow_dirty, &args.diff),
+            )
+            .await;
+        }
+        // -------------------------------------------------------------------------------------
+        // COMANDO SET
+        // -------------------------------------------------------------------------------------
+        Commands::Set(args) => {
+            let config_path = get_config_path().await;
+            let Some(mut config) = load_config(&config_path).await else {
+                config_error(report, &config_path);
+            };
+            let current_version = match transaction_current_version(&config) {
+                Ok(version) => version,
+                Err(e) => {
+                    report.fail("invalid_version", format!("Invalid current version in config: {}", e));
+                    report.finish();
+                }
+            };
+            report.current_version = Some(current_version.clone());
 
-            match Config::read(&config_path).await {
-                Some(mut config) => {
-                    match calculate_version(
-                        &config.current_version,
-                        change_type,
-                        Operation::Decrement,
-                    ) {
-                        Ok(target_version) => {
-                            println!("Current version: {}", config.current_version);
-                            println!("Target downgrade version (preview): {}", target_version);
-                            
-                            // El string de reemplazo usa los grupos de captura $1 y $2.
-                            let replacement_to = format!("${{1}}{}${{2}}", target_version);
-
-                            let current_version = config.current_version.clone();
-                            let mut modified_files = Vec::new();
-                            let mut all_files_verified = true;
-
-                            // FASE 1: VERIFICACIÓN Y SIMULACIÓN
-                            println!("-- Verifying and simulating changes (Downgrade)... --");
-
-                            for replace in &config.replaces {
-                                
-                                // APLICAR LÓGICA DE ENVOLTURA AUTOMÁTICA
-                                let wrapped_search = wrap_search_pattern(replace.pattern.as_str());
-
-                                // El patrón de búsqueda (FROM) usa la versión actual
-                                let pattern_from = format!(
-                                    "(?m){}",
-                                    wrapped_search.replace(
-                                        "{{current_version}}",
-                                        &current_version.replace(".", "\\."))
-                                );
-                                debug!("Pattern FROM: {}", pattern_from);
-
-                                // El patrón de verificación (TO) usa la versión de destino
-                                debug!("Wrapped search pattern: {}", wrapped_search);
-                                let pattern_to = format!(
-                                    "(?m){}",
-                                    wrapped_search.replace(
-                                        "{{current_version}}",
-                                        &target_version.replace(".", "\\."))
-                                );
-                                debug!("Pattern TO: {}", pattern_to);
-                                
-                                debug!(
-                                    "Simulating file: {} | FROM: {} | TO (Verif): {}",
-                                    replace.file, pattern_from, pattern_to
-                                );
-
-                                match simulate_replacement(
-                                    replace.file.as_str(),
-                                    &pattern_from,
-                                    &replacement_to, 
-                                    &pattern_to,
-                                )
-                                .await
-                                {
-                                    Ok(content) => {
-                                        modified_files.push((replace.file.clone(), content));
-                                    }
-                                    Err(e) => {
-                                        error!(File=%replace.file, "CRITICAL FAILURE SIMULATION: {}", e);
-                                        all_files_verified = false;
-                                        break;
-                                    }
-                                }
-                            }
-
-                            // FASE 2: EJECUCIÓN (sin cambios)
-                            if all_files_verified {
-                                println!("-- Applying changes... --");
-
-                                for (file_path, content) in modified_files {
-                                    match apply_replacement(file_path.as_str(), &content).await {
-                                        Ok(_) => {
-                                            println!("✅ Updated (Downgrade): {}", file_path);
-                                        }
-                                        Err(e) => {
-                                            error!(File=%file_path, "CRITICAL WRITE FAILURE: {}", e);
-                                        }
-                                    }
-                                }
-
-                                config.current_version = target_version.clone();
-                                config.write(&config_path).await;
-                                println!(
-                                    "\n🎉 Success: Config version updated to {}",
-                                    config.current_version
-                                );
-                            } else {
-                                error!(
-                                    "\nDowngrade aborted due to simulation failures in files."
-                                );
-                            }
-                        }
-                        Err(e) => {
-                            error!("Error calculating the downgrade version: {}", e);
-                        }
-                    }
+            let target_version = match calculate_target_version(&config, &args.version, args.force) {
+                Ok(version) => version,
+                Err(e) => {
+                    report.fail("invalid_target_version", e);
+                    report.finish();
                 }
-                None => error!("Failed to read config file at {}", config_path.display()),
+            };
+
+            report.print(format_args!("Current version: {}", current_version));
+            report.print(format_args!("Target version: {}", target_version));
+            report.new_version = Some(target_version.clone());
+
+            if let Some(updated_files) = apply_version_change(
+                &mut report,
+                &mut config,
+                &config_path,
+                &current_version,
+                &target_version,
+                "Set",
+                &ChangeOptions::new(cli.allow_dirty, &args.diff),
+            )
+            .await
+            {
+                commit_version_change(&mut report, &config, &config_path, &current_version, updated_files).await;
             }
         }
         // -------------------------------------------------------------------------------------
-        // COMANDO PREVIEW (sin cambios)
+        // COMANDO PREVIEW (DRY RUN)
         // -------------------------------------------------------------------------------------
         Commands::Preview(args) => {
-            let (change_type, _) = get_version_change(args);
             let config_path = get_config_path().await;
+            let Some(config) = load_config(&config_path).await else {
+                config_error(report, &config_path);
+            };
+            let current_version = match transaction_current_version(&config) {
+                Ok(version) => version,
+                Err(e) => {
+                    report.fail("invalid_version", format!("Invalid current version in config: {}", e));
+                    report.finish();
+                }
+            };
+            report.current_version = Some(current_version.clone());
 
-            let operation = Operation::Increment;
-
-            match Config::read(&config_path).await {
-                Some(config) => {
-                    match calculate_version(&config.current_version, change_type, operation) {
-                        Ok(new_version) => {
-                            println!("Current version: {}", config.current_version);
-                            println!("Preview version (Increment): {}", new_version);
-                        }
-                        Err(e) => {
-                            error!("Error calculating the version: {}", e);
-                        }
+            let (action, result) = if let Some(target_version) = &args.set {
+                ("Set", calculate_target_version(&config, target_version, args.force))
+            } else if args.downgrade {
+                ("Downgrade", calculate_previous_version(&config, &args.version))
+            } else {
+                let (change_type, auto_change) = match resolve_version_change(&config, &args.version).await {
+                    Ok(resolved) => resolved,
+                    Err(e) => {
+                        report.fail("version_inference_failed", format!("Error inferring the version change: {}", e));
+                        report.finish();
+                    }
+                };
+                if let Some(auto_change) = &auto_change {
+                    report_auto_change(&mut report, auto_change, true);
+                    if change_type.is_none() {
+                        report.fail(
+                            "nothing_to_release",
+                            "No feat, fix or breaking change commits found. Nothing to release.",
+                        );
+                        report.finish();
                     }
                 }
-                None => error!("Failed to read config file at {}", config_path.display()),
+                let result = calculate_next_version(
+                    &config,
+                    change_type,
+                    get_pre_release_change(&args.pre_release),
+                    Local::now().date_naive(),
+                );
+                ("Upgrade", result)
+            };
+            let new_version = match result {
+                Ok(version) => version,
+                Err(e) => {
+                    report.fail("version_calculation_failed", format!("Error calculating the version: {}", e));
+                    report.finish();
+                }
+            };
+
+            report.print(format_args!("Current version: {}", current_version));
+            report.print(format_args!("Preview version ({}): {}", action, new_version));
+            report.new_version = Some(new_version.clone());
+
+            preview_version_change(
+                &mut report,
+                &config,
+                &config_path,
+                &current_version,
+                &new_version,
+                action,
+                &ChangeOptions::new(cli.allow_dirty, &args.diff),
+            )
+            .await;
+        }
+        // -------------------------------------------------------------------------------------
+        // COMANDO INIT
+        // -------------------------------------------------------------------------------------
+        Commands::Init(args) => {
+            let config_path = get_config_path().await;
+            if config_path.exists() && !args.force {
+                report.fail(
+                    "config_exists",
+                    format!("Config file {} already exists. Use --force to overwrite it.", config_path.display()),
+                );
+                report.finish();
+            }
+
+            let proposal = match propose_config().await {
+                Ok(Some(proposal)) => proposal,
+                Ok(None) => {
+                    report.fail(
+                        "no_manifest_found",
+                        "No known manifest with a version found (Cargo.toml, package.json, pyproject.toml, ...).",
+                    );
+                    report.finish();
+                }
+                Err(e) => {
+                    report.fail("unknown_scheme", e);
+                    report.finish();
+                }
+            };
+            let version = &proposal.config.current_version;
+            report.current_version = Some(version.clone());
+
+            report.print(format_args!("Detected version: {}", version));
+            for file in &proposal.detected {
+                report.print(format_args!("✅ {}", file.file));
+                report.file(&file.file, "detected");
+            }
+            for file in &proposal.skipped {
+                report.print(format_args!(
+                    "⚠️  {}: skipped, it has version {} instead of {}",
+                    file.file, file.version, version
+                ));
+                report.file(&file.file, "skipped").found = vec![file.version.clone()];
+            }
+            if proposal.config.cargo_workspace {
+                report.print("📦 Cargo workspace detected: members are updated automatically.");
+            }
+            if proposal.config.sync_lockfiles {
+                report.print("🔒 Lockfiles detected: they are kept in sync.");
+            }
+
+            match proposal.config.write(&config_path).await {
+                Ok(_) => report.print(format_args!("\n🎉 Created {}", config_path.display())),
+                Err(e) => report.record("config_write_failed", Some(&config_path.display().to_string()), e.to_string()),
             }
         }
         // -------------------------------------------------------------------------------------
-        // COMANDO SHOW (sin cambios)
+        // COMANDO SHOW
         // -------------------------------------------------------------------------------------
         Commands::Show => {
             let config_path = get_config_path().await;
-            match Config::read(&config_path).await {
-                Some(config) => {
-                    println!("{}", config.current_version);
+            let Some(config) = Config::read(&config_path).await else {
+                config_error(report, &config_path);
+            };
+            let resolved = resolve_current_version(&config).await;
+            let version = match validate_version(&config, &resolved.version) {
+                Ok(version) => version,
+                Err(e) => {
+                    report.fail("invalid_version", format!("Invalid current version: {}", e));
+                    report.finish();
+                }
+            };
+
+            // Con `version_source: config` la salida es solo la versión, para poder capturarla
+            // en scripts (`VERSION=$(vampus show)`).
+            report.print(&version);
+            report.source = Some(match resolved.source {
+                VersionSource::Git => "git",
+                VersionSource::Config => "config",
+            });
+            report.source_tag = resolved.tag.clone();
+            if config.version_source == VersionSource::Git {
+                match &resolved.tag {
+                    Some(tag) => report.print(format_args!("Source: git tag {}", tag)),
+                    None => report.print("Source: config file"),
+                }
+                if resolved.source == VersionSource::Config {
+                    report.warn(format!(
+                        "no tags matching '{}*' found; falling back to the config version.",
+                        config.tag_prefix
+                    ));
+                } else if compare_versions(&config, &version, &config.current_version)
+                    .map_or(true, |ordering| ordering.is_ne())
+                {
+                    report.warn(format!(
+                        "the config file says {} but the latest tag says {}.",
+                        config.current_version, version
+                    ));
+                }
+            }
+            report.current_version = Some(version);
+        }
+        // -------------------------------------------------------------------------------------
+        // COMANDO CHECK
+        // -------------------------------------------------------------------------------------
+        Commands::Check => {
+            let config_path = get_config_path().await;
+            let Some(config) = load_config(&config_path).await else {
+                config_error(report, &config_path);
+            };
+            if let Err(e) = validate_version(&config, &config.current_version) {
+                report.fail("invalid_version", format!("Invalid current version: {}", e));
+                report.finish();
+            }
+            report.current_version = Some(config.current_version.clone());
+
+            let checks = check_files(&config).await;
+            for check in &checks {
+                let (line, message) = match &check.status {
+                    CheckStatus::Ok => (format!("✅ {}: OK", check.file), None),
+                    CheckStatus::Missing(reason) => {
+                        (format!("❌ {}: missing ({})", check.file, reason), Some(reason.clone()))
+                    }
+                    CheckStatus::Mismatched(found) => {
+                        let message = format!("found {}", found.join(", "));
+                        (format!("❌ {}: mismatched ({})", check.file, message), Some(message))
+                    }
+                    CheckStatus::Ambiguous(lines) => {
+                        let message = format!(
+                            "{} matches on lines {}",
+                            lines.len(),
+                            lines.iter().map(usize::to_string).collect::<Vec<_>>().join(", ")
+                        );
+                        (format!("⚠️  {}: ambiguous ({})", check.file, message), Some(message))
+                    }
+                };
+                report.print(line);
+
+                let file = report.file(&check.file, check.status.code());
+                match &check.status {
+                    CheckStatus::Mismatched(found) => file.found = found.clone(),
+                    CheckStatus::Ambiguous(lines) => file.lines = lines.clone(),
+                    _ => {}
+                }
+                file.message = message.clone();
+                if let Some(message) = message {
+                    report.record(check.status.code(), Some(&check.file), message);
+                }
+            }
+
+            let failed = checks.iter().filter(|check| !check.status.is_ok()).count();
+            if failed > 0 {
+                report.fail(
+                    "check_failed",
+                    format!(
+                        "{} of {} file(s) are not at version {}",
+                        failed,
+                        checks.len(),
+                        config.current_version
+                    ),
+                );
+            } else {
+                report.print(format_args!(
+                    "\n🎉 All {} file(s) are at version {}",
+                    checks.len(),
+                    config.current_version
+                ));
+            }
+        }
+    }
+
+    report.finish();
+}
+
+/// Termina el comando cuando no se ha podido leer la configuración.
+fn config_error(mut report: Report, config_path: &Path) -> ! {
+    report.fail(
+        "config_error",
+        format!("Failed to read config file at {}", config_path.display()),
+    );
+    report.finish()
+}
+
+/// Versión actual con la que trabajan `Upgrade`, `Downgrade`, `Set` y su `Preview`. Se valida
+/// con el esquema configurado, pero se devuelve tal y como está escrita: es la grafía que
+/// aparece en los archivos (PEP 440 admite varias para la misma versión, como `1.0-post1` y
+/// `1.0.post1`). La forma normalizada solo se usa para calcular la nueva versión.
+fn transaction_current_version(config: &Config) -> Result<String, String> {
+    validate_version(config, &config.current_version)?;
+    Ok(config.current_version.clone())
+}
+
+/// Lee la configuración y, con `version_source: git`, sustituye `current_version` por la
+/// versión de la última etiqueta.
+async fn load_config(config_path: &PathBuf) -> Option<Config> {
+    let mut config = Config::read(config_path).await?;
+    let resolved = resolve_current_version(&config).await;
+    if let Some(tag) = &resolved.tag {
+        debug!("Current version {} taken from git tag {}", resolved.version, tag);
+    }
+    config.current_version = resolved.version;
+    Some(config)
+}
+
+/// Explica qué commits han decidido el tipo de cambio en modo `--auto`. Con `detailed`
+/// se lista cada commit analizado.
+fn report_auto_change(report: &mut Report, auto_change: &AutoChange, detailed: bool) {
+    let since = auto_change.tag.as_deref().unwrap_or("the first commit");
+    report.print(format_args!(
+        "Analyzed {} commits since {}.",
+        auto_change.commits.len(),
+        since
+    ));
+    if detailed {
+        for classified in &auto_change.commits {
+            report.print(format_args!(
+                "  [{:<5}] {} {}",
+                classified.change_type.unwrap_or("-"),
+                classified.commit.short_sha(),
+                classified.commit.subject
+            ));
+        }
+    }
+    if let Some(change_type) = auto_change.change_type {
+        report.print(format_args!("Inferred change type: {}", change_type));
+    }
+    report.change_type = auto_change.change_type;
+}
+
+// =============================================================================================
+// TRANSACCIÓN DE CAMBIO DE VERSIÓN
+// =============================================================================================
+
+/// Opciones de la transacción de cambio de versión.
+struct ChangeOptions {
+    allow_dirty: bool,
+    diff_context: Option<usize>,
+}
+
+impl ChangeOptions {
+    fn new(allow_dirty: bool, diff: &DiffArgs) -> Self {
+        Self {
+            allow_dirty,
+            diff_context: diff.diff.then_some(diff.diff_context),
+        }
+    }
+}
+
+/// Construye los patrones FROM, el reemplazo y el patrón de verificación TO de una regla RegEx.
+fn regex_patterns(
+    context: &TemplateContext,
+    replace: &Replace,
+    new_version: &str,
+) -> Result<(String, String, String), String> {
+    match &replace.replace {
+        // Plantilla explícita: cada coincidencia de `pattern` se sustituye por el texto
+        // renderizado, que también es lo que se verifica tras la simulación.
+        Some(template) => {
+            let pattern_from = context.render_pattern(&replace.pattern)?;
+            let rendered = context.render(template)?;
+            Ok((
+                format!("(?m){}", pattern_from),
+                rendered.replace('$', "$$"),
+                format!("(?m){}", regex::escape(&rendered)),
+            ))
+        }
+        None => {
+            // APLICAR LÓGICA DE ENVOLTURA AUTOMÁTICA
+            let wrapped_search = wrap_search_pattern(replace.pattern.as_str());
+            debug!("Wrapped search pattern: {}", wrapped_search);
+
+            // El patrón de búsqueda (FROM) usa la versión actual y el de verificación
+            // (TO) la nueva; el reemplazo conserva los grupos de captura $1 y $2.
+            let verify_search = wrapped_search.replace("{{current_version}}", "{{new_version}}");
+            let patterns = (
+                format!("(?m){}", context.render_pattern(&wrapped_search)?),
+                format!("${{1}}{}${{2}}", new_version),
+                format!("(?m){}", context.render_pattern(&verify_search)?),
+            );
+            debug!("Pattern FROM: {}", patterns.0);
+            debug!("Replacement TO string: {}", patterns.1);
+            debug!("Pattern TO: {}", patterns.2);
+            Ok(patterns)
+        }
+    }
+}
+
+/// FASE 1 de la transacción: simula y verifica cada `Replace` sin escribir nada.
+/// Devuelve el resultado de cada regla junto con su archivo, sin detenerse en el primer fallo.
+async fn simulate_version_change(
+    report: &Report,
+    config: &Config,
+    current_version: &str,
+    new_version: &str,
+    action: &str,
+) -> Vec<(String, Result<SimulatedFile, io::Error>)> {
+    let context = TemplateContext::new(config, current_version, new_version).await;
+    let mut results = Vec::new();
+    let mut contents: HashMap<String, String> = HashMap::new();
+
+    report.print(format_args!("-- Verifying and simulating changes ({})... --", action));
+
+    for replace in &config.replaces {
+        let expanded = replace.structured_path().and_then(|structured| {
+            Ok((structured, replace.match_bounds()?, expand_replace_files(replace)?))
+        });
+        let (structured, bounds, files) = match expanded {
+            Ok(expanded) => expanded,
+            Err(e) => {
+                results.push((
+                    replace.file.to_string(),
+                    Err(io::Error::new(io::ErrorKind::NotFound, e)),
+                ));
+                continue;
+            }
+        };
+
+        let patterns = if structured.is_some() {
+            None
+        } else {
+            match regex_patterns(&context, replace, new_version) {
+                Ok(patterns) => Some(patterns),
+                Err(e) => {
+                    results.push((
+                        replace.file.to_string(),
+                        Err(io::Error::new(io::ErrorKind::InvalidInput, e)),
+                    ));
+                    continue;
+                }
+            }
+        };
+
+        for file in files {
+            // Si una regla anterior ya modificó el archivo, se parte de ese contenido.
+            let content = match contents.get(&file) {
+                Some(content) => Ok(content.clone()),
+                None => read_text_file(&file).await,
+            };
+
+            let result = content.and_then(|content| match (structured, &patterns) {
+                (Some((format, key_path)), _) => {
+                    debug!("Simulating file: {} | {:?} key: {}", file, format, key_path);
+                    simulate_structured_replacement(format, &file, content, key_path, current_version, new_version)
+                }
+                (None, Some((pattern_from, replacement_to, pattern_to))) => {
+                    debug!(
+                        "Simulating file: {} | FROM: {} | TO (Verif): {}",
+                        file, pattern_from, pattern_to
+                    );
+                    simulate_replacement(&file, content, pattern_from, replacement_to, pattern_to, bounds)
                 }
-                None => error!("Failed to read config file at {}", config_path.display()),
+                (None, None) => unreachable!("regex patterns are built for every non-structured rule"),
+            });
+            if let Ok(simulated) = &result {
+                contents.insert(file.clone(), simulated.modified.clone());
+            }
+            results.push((file, result));
+        }
+    }
+
+    if config.cargo_workspace {
+        for (file, result) in simulate_cargo_workspace(&contents, current_version, new_version).await {
+            if let Ok(simulated) = &result {
+                contents.insert(file.clone(), simulated.modified.clone());
+            }
+            results.push((file, result));
+        }
+    }
+
+    // Los lockfiles van al final para partir de los manifiestos ya actualizados.
+    if config.sync_lockfiles {
+        results.extend(simulate_lockfiles(&contents, current_version, new_version).await);
+    }
+
+    results
+}
+
+/// Separa los cambios simulados correctos, registrando cada fallo de simulación.
+/// Devuelve `None` si algún archivo no ha superado la verificación.
+fn collect_simulated_files(
+    report: &mut Report,
+    results: Vec<(String, Result<SimulatedFile, io::Error>)>,
+) -> Option<Vec<SimulatedFile>> {
+    let mut modified_files = Vec::new();
+    let mut all_files_verified = true;
+
+    for (file, result) in results {
+        match result {
+            Ok(simulated) => {
+                report.file(&file, "ok").matches = Some(simulated.matches);
+                modified_files.push(simulated);
+            }
+            Err(e) => {
+                error!(File=%file, "CRITICAL SIMULATION FAILURE: {}", e);
+                report.file(&file, "error").message = Some(e.to_string());
+                report.record("simulation_failed", Some(&file), e.to_string());
+                all_files_verified = false;
+            }
+        }
+    }
+
+    all_files_verified.then(|| merge_simulated_files(modified_files))
+}
+
+/// Ejecuta `Preview` como un dry run completo: comprobaciones previas, simulación de
+/// cada `Replace` con su número de coincidencias y, opcionalmente, el diff. Los motivos por
+/// los que el comando real fallaría quedan registrados como errores en el informe.
+async fn preview_version_change(
+    report: &mut Report,
+    config: &Config,
+    config_path: &Path,
+    current_version: &str,
+    new_version: &str,
+    action: &str,
+    options: &ChangeOptions,
+) {
+    let results = simulate_version_change(report, config, current_version, new_version, "Preview").await;
+    let mut modified_files = Vec::new();
+    for (file, result) in results {
+        match result {
+            Ok(simulated) => {
+                report.print(format_args!("✅ {}: {} match(es)", file, simulated.matches));
+                report.file(&file, "ok").matches = Some(simulated.matches);
+                modified_files.push(simulated);
+            }
+            Err(e) => {
+                report.print(format_args!("❌ {}: {}", file, e));
+                report.file(&file, "error").message = Some(e.to_string());
+                report.record("simulation_failed", Some(&file), e.to_string());
             }
         }
     }
+
+    match run_preflight_checks(config, options.allow_dirty).await {
+        Ok(_) => report.print("✅ Pre-flight checks passed"),
+        Err(e) => {
+            report.print(format_args!("❌ Pre-flight checks: {}", e));
+            report.record("preflight_failed", None, e);
+        }
+    }
+
+    if action != "Downgrade"
+        && let Err(e) = render_release_templates(config, current_version, new_version).await
+    {
+        report.print(format_args!("❌ {}", e));
+        report.record("invalid_template", None, e);
+    }
+
+    if let Some(context) = options.diff_context {
+        let modified_files = merge_simulated_files(modified_files);
+        print_diffs(report, config, config_path, &modified_files, new_version, context).await;
+    }
+
+    if report.has_errors() {
+        report.fail(
+            "dry_run_failed",
+            format!("Dry run FAILED: '{}' to {} would fail.", action.to_lowercase(), new_version),
+        );
+    } else {
+        report.print(format_args!(
+            "\n🔍 Dry run OK: '{}' to {} would succeed.",
+            action.to_lowercase(),
+            new_version
+        ));
+    }
+}
+
+/// Muestra el diff unificado de cada archivo simulado y del propio archivo de configuración.
+/// Solo existe en el formato humano.
+async fn print_diffs(
+    report: &mut Report,
+    config: &Config,
+    config_path: &Path,
+    modified_files: &[SimulatedFile],
+    new_version: &str,
+    context: usize,
+) {
+    if report.is_json() {
+        report.warn("--diff is ignored with --output json".to_string());
+        return;
+    }
+    let color = io::stdout().is_terminal();
+
+    for simulated in modified_files {
+        print!(
+            "{}",
+            render_unified_diff(&simulated.path, &simulated.original, &simulated.modified, context, color)
+        );
+    }
+
+    let original = tokio::fs::read_to_string(config_path).await.unwrap_or_default();
+    let mut updated = config.clone();
+    updated.current_version = new_version.to_string();
+    match updated.to_yaml() {
+        Ok(modified) => {
+            let name = config_path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_else(|| config_path.display().to_string());
+            print!("{}", render_unified_diff(&name, &original, &modified, context, color));
+        }
+        Err(e) => error!("Failed to render the config file diff: {}", e),
+    }
+}
+
+/// Ejecuta la transacción en dos fases compartida por `Upgrade`, `Downgrade` y `Set`:
+/// primero simula y verifica cada `Replace`, y solo si todos pasan escribe los archivos
+/// y actualiza la versión en el archivo de configuración. Devuelve los archivos escritos,
+/// o `None` si alguna fase ha fallado.
+async fn apply_version_change(
+    report: &mut Report,
+    config: &mut Config,
+    config_path: &Path,
+    current_version: &str,
+    new_version: &str,
+    action: &str,
+    options: &ChangeOptions,
+) -> Option<Vec<String>> {
+    // FASE 0: COMPROBACIONES PREVIAS
+    if let Err(e) = run_preflight_checks(config, options.allow_dirty).await {
+        report.fail("preflight_failed", format!("{} aborted. Pre-flight check failed: {}", action, e));
+        return None;
+    }
+    // Las plantillas del commit y la etiqueta se validan antes de escribir nada.
+    if action != "Downgrade"
+        && let Err(e) = render_release_templates(config, current_version, new_version).await
+    {
+        report.fail("invalid_template", format!("{} aborted. {}", action, e));
+        return None;
+    }
+
+    // FASE 1: VERIFICACIÓN Y SIMULACIÓN
+    let results = simulate_version_change(report, config, current_version, new_version, action).await;
+    let Some(modified_files) = collect_simulated_files(report, results) else {
+        report.fail("aborted", format!("{} aborted. No changes were written to files.", action));
+        return None;
+    };
+
+    if let Some(context) = options.diff_context {
+        print_diffs(report, config, config_path, &modified_files, new_version, context).await;
+    }
+
+    // FASE 2: EJECUCIÓN
+    report.print("-- Applying changes... --");
+
+    let mut written_files = Vec::new();
+    let mut all_files_written = true;
+
+    // Los archivos sin cambios (reglas opcionales sin coincidencias) no se reescriben.
+    for simulated in &modified_files {
+        if simulated.modified == simulated.original {
+            report.set_status(&simulated.path, "unchanged");
+            continue;
+        }
+        match apply_replacement(simulated.path.as_str(), &simulated.modified).await {
+            Ok(_) => {
+                report.print(format_args!("✅ Updated: {}", simulated.path));
+                report.set_status(&simulated.path, "updated");
+                written_files.push(simulated);
+            }
+            Err(e) => {
+                error!(File=%simulated.path, "CRITICAL WRITE FAILURE: {}", e);
+                report.set_status(&simulated.path, "error");
+                report.record("write_failed", Some(&simulated.path), e.to_string());
+                all_files_written = false;
+                break;
+            }
+        }
+    }
+
+    // La configuración solo se actualiza si todos los archivos se han escrito.
+    if all_files_written {
+        let previous_version = std::mem::replace(&mut config.current_version, new_version.to_string());
+        if let Err(e) = config.write(config_path).await {
+            config.current_version = previous_version;
+            report.record("config_write_failed", Some(&config_path.display().to_string()), e.to_string());
+            all_files_written = false;
+        }
+    }
+
+    // ROLLBACK: se restauran los archivos ya escritos y la configuración queda intacta.
+    if !all_files_written {
+        if rollback_replacements(report, &written_files).await {
+            report.fail("aborted", format!("{} aborted. All modified files were restored.", action));
+        } else {
+            report.fail(
+                "rollback_failed",
+                format!("{} aborted. Some files could NOT be restored; check them manually.", action),
+            );
+        }
+        return None;
+    }
+
+    report.print(format_args!(
+        "\n🎉 Success: Config version updated to {}",
+        config.current_version
+    ));
+
+    Some(written_files.iter().map(|simulated| simulated.path.clone()).collect())
+}
+
+/// Renderiza el mensaje de commit y el nombre de la etiqueta (si `commit`/`tag` están
+/// activados) para detectar plantillas inválidas antes de modificar ningún archivo.
+async fn render_release_templates(
+    config: &Config,
+    current_version: &str,
+    new_version: &str,
+) -> Result<(String, String), String> {
+    if !config.commit && !config.tag {
+        return Ok((String::new(), String::new()));
+    }
+    let context = TemplateContext::new(config, current_version, new_version).await;
+    let message = context
+        .render(&config.message)
+        .map_err(|e| format!("Invalid commit message template: {}", e))?;
+    let tag_name = if config.tag {
+        context
+            .render(&config.tag_name)
+            .map_err(|e| format!("Invalid tag name template: {}", e))?
+    } else {
+        String::new()
+    };
+    Ok((message, tag_name))
+}
+
+/// Crea el commit y la etiqueta anotada de la nueva versión si `commit`/`tag` están
+/// activados. El commit incluye solo los archivos modificados y el de configuración.
+async fn commit_version_change(
+    report: &mut Report,
+    config: &Config,
+    config_path: &Path,
+    current_version: &str,
+    updated_files: Vec<String>,
+) {
+    let (message, tag_name) =
+        match render_release_templates(config, current_version, &config.current_version).await {
+            Ok(rendered) => rendered,
+            Err(e) => {
+                report.fail("invalid_template", e);
+                return;
+            }
+        };
+
+    if config.commit {
+        let mut paths = updated_files;
+        paths.push(config_path.display().to_string());
+        match git::commit_files(&paths, &message).await {
+            Ok(_) => {
+                report.print(format_args!("📝 Committed: {}", message));
+                report.commit = Some(message.clone());
+            }
+            Err(e) => {
+                report.fail("commit_failed", format!("Failed to commit the version change: {}", e));
+                return;
+            }
+        }
+    }
+
+    if config.tag {
+        match git::create_tag(&tag_name, &message).await {
+            Ok(_) => {
+                report.print(format_args!("🏷️  Tagged: {}", tag_name));
+                report.tag = Some(tag_name);
+            }
+            Err(e) => report.fail("tag_failed", format!("Failed to create tag '{}': {}", tag_name, e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use config::{FileSpec, Scheme};
+
+    #[tokio::test]
+    async fn non_normalized_pep440_version_is_found_in_files() {
+        let mut config = Config::new("1.3.2-post1".to_string());
+        config.scheme = Scheme::Pep440;
+        let replace = Replace {
+            file: FileSpec::Single("v.txt".to_string()),
+            pattern: "^v={{current_version}}$".to_string(),
+            ..Replace::default()
+        };
+
+        let current_version = transaction_current_version(&config).unwrap();
+        assert_eq!(current_version, "1.3.2-post1");
+        let new_version = calculate_next_version(
+            &config,
+            None,
+            utils::PreReleaseChange::Post,
+            Local::now().date_naive(),
+        )
+        .unwrap();
+        assert_eq!(new_version, "1.3.2.post2");
+
+        let context = TemplateContext::new(&config, &current_version, &new_version).await;
+        let (pattern_from, replacement_to, pattern_to) = regex_patterns(&context, &replace, &new_version).unwrap();
+        let simulated = simulate_replacement(
+            "v.txt",
+            "v=1.3.2-post1\n".to_string(),
+            &pattern_from,
+            &replacement_to,
+            &pattern_to,
+            replace.match_bounds().unwrap(),
+        )
+        .unwrap();
+        assert_eq!(simulated.modified, "v=1.3.2.post2\n");
+    }
 }
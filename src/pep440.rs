@@ -0,0 +1,378 @@
+use regex::Regex;
+use std::{cmp::Ordering, fmt, str::FromStr};
+
+use crate::utils::PreReleaseChange;
+
+// =============================================================================================
+// ESQUEMA PEP 440 (paquetes de Python)
+// =============================================================================================
+
+// RegEx del apéndice B de PEP 440 (admite las variantes que pip normaliza).
+const PEP440_PATTERN: &str = r"(?ix)^\s*v?
+    (?:(?P<epoch>[0-9]+)!)?
+    (?P<release>[0-9]+(?:\.[0-9]+)*)
+    (?P<pre>[-_\.]?(?P<pre_l>alpha|beta|preview|pre|rc|a|b|c)[-_\.]?(?P<pre_n>[0-9]+)?)?
+    (?P<post>(?:-(?P<post_n1>[0-9]+))|(?:[-_\.]?(?P<post_l>post|rev|r)[-_\.]?(?P<post_n2>[0-9]+)?))?
+    (?P<dev>[-_\.]?(?P<dev_l>dev)[-_\.]?(?P<dev_n>[0-9]+)?)?
+    (?:\+(?P<local>[a-z0-9]+(?:[-_\.][a-z0-9]+)*))?
+    \s*$";
+
+/// Fase de una pre-release PEP 440, en orden de precedencia.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum PreKind {
+    Alpha,
+    Beta,
+    Rc,
+}
+
+impl PreKind {
+    fn parse(label: &str) -> Result<Self, String> {
+        match label.to_ascii_lowercase().as_str() {
+            "a" | "alpha" => Ok(PreKind::Alpha),
+            "b" | "beta" => Ok(PreKind::Beta),
+            "rc" | "c" | "pre" | "preview" => Ok(PreKind::Rc),
+            _ => Err(format!("Invalid PEP 440 pre-release label '{}' (expected a, b or rc)", label)),
+        }
+    }
+
+    fn next(self) -> Option<Self> {
+        match self {
+            PreKind::Alpha => Some(PreKind::Beta),
+            PreKind::Beta => Some(PreKind::Rc),
+            PreKind::Rc => None,
+        }
+    }
+}
+
+impl fmt::Display for PreKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PreKind::Alpha => write!(f, "a"),
+            PreKind::Beta => write!(f, "b"),
+            PreKind::Rc => write!(f, "rc"),
+        }
+    }
+}
+
+/// Versión PEP 440: `[N!]N(.N)*[{a|b|rc}N][.postN][.devN][+local]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pep440Version {
+    pub epoch: u64,
+    pub release: Vec<u64>,
+    pub pre: Option<(PreKind, u64)>,
+    pub post: Option<u64>,
+    pub dev: Option<u64>,
+    pub local: Option<String>,
+}
+
+impl Pep440Version {
+    fn from_release(epoch: u64, release: Vec<u64>) -> Self {
+        Self {
+            epoch,
+            release,
+            pre: None,
+            post: None,
+            dev: None,
+            local: None,
+        }
+    }
+
+    /// Una versión es pre-release si tiene segmento `pre` o `dev`.
+    pub fn is_prerelease(&self) -> bool {
+        self.pre.is_some() || self.dev.is_some()
+    }
+
+    // Clave de ordenación equivalente a la de `packaging.version`.
+    #[allow(clippy::type_complexity)]
+    fn sort_key(&self) -> (u64, Vec<u64>, (i8, Option<PreKind>, u64), (i8, u64), (i8, u64)) {
+        let mut release = self.release.clone();
+        while release.len() > 1 && release.last() == Some(&0) {
+            release.pop();
+        }
+        let pre = match (self.pre, self.post, self.dev) {
+            // Una versión solo-dev (1.0.dev1) va antes que cualquier pre-release.
+            (None, None, Some(_)) => (-1, None, 0),
+            (None, _, _) => (1, None, 0),
+            (Some((kind, n)), _, _) => (0, Some(kind), n),
+        };
+        let post = self.post.map_or((-1, 0), |n| (0, n));
+        let dev = self.dev.map_or((1, 0), |n| (0, n));
+        (self.epoch, release, pre, post, dev)
+    }
+
+    /// Calcula la siguiente versión: `change_type` incrementa el segmento de release
+    /// (major, minor o patch) y `change` gestiona los segmentos pre, post y dev.
+    pub fn bump(&self, change_type: Option<&str>, change: PreReleaseChange) -> Result<Self, String> {
+        match change {
+            PreReleaseChange::None => self.bump_release(change_type.unwrap_or("patch")),
+            PreReleaseChange::Release => {
+                if !self.is_prerelease() {
+                    return Err(format!("Version {} is not a pre-release", self));
+                }
+                Ok(Self::from_release(self.epoch, self.release.clone()))
+            }
+            PreReleaseChange::Promote => {
+                let (kind, _) = self
+                    .pre
+                    .ok_or_else(|| format!("Version {} has no pre-release segment to promote", self))?;
+                let next = kind.next().ok_or_else(|| {
+                    format!("'{}' is the last pre-release phase; use --release to publish the final version", kind)
+                })?;
+                let mut version = Self::from_release(self.epoch, self.release.clone());
+                version.pre = Some((next, 1));
+                Ok(version)
+            }
+            PreReleaseChange::Start(label) => {
+                let kind = match label.as_deref() {
+                    Some(label) => Some(PreKind::parse(label)?),
+                    None => None,
+                };
+                if change_type.is_some() || !self.is_prerelease() {
+                    let mut version = Self::from_release(self.epoch, self.release.clone())
+                        .bump_release(change_type.unwrap_or("patch"))?;
+                    version.pre = Some((kind.unwrap_or(PreKind::Alpha), 1));
+                    return Ok(version);
+                }
+
+                let mut version = Self::from_release(self.epoch, self.release.clone());
+                version.pre = match (self.pre, kind) {
+                    (Some((current, n)), None) => Some((current, self.next_counter(n))),
+                    (Some((current, n)), Some(kind)) if kind == current => {
+                        Some((current, self.next_counter(n)))
+                    }
+                    (Some((current, _)), Some(kind)) if kind < current => {
+                        return Err(format!("Pre-release '{}' would go backwards from {}", kind, self));
+                    }
+                    (_, kind) => Some((kind.unwrap_or(PreKind::Alpha), 1)),
+                };
+                Ok(version)
+            }
+            PreReleaseChange::Post => {
+                if change_type.is_some() {
+                    return Err("--post cannot be combined with --major, --minor or --patch".to_string());
+                }
+                let mut version = self.clone();
+                version.post = Some(self.post.map_or(1, |n| n + 1));
+                version.dev = None;
+                version.local = None;
+                Ok(version)
+            }
+            PreReleaseChange::Dev => {
+                if change_type.is_none()
+                    && let Some(n) = self.dev
+                {
+                    let mut version = self.clone();
+                    version.dev = Some(n + 1);
+                    version.local = None;
+                    return Ok(version);
+                }
+                // `1.4.0a1` + dev es una dev release de la siguiente pre-release: `1.4.0a2.dev1`.
+                if change_type.is_none()
+                    && let Some((kind, n)) = self.pre
+                {
+                    let mut version = Self::from_release(self.epoch, self.release.clone());
+                    version.pre = Some((kind, n + 1));
+                    version.dev = Some(1);
+                    return Ok(version);
+                }
+                let mut version = Self::from_release(self.epoch, self.release.clone())
+                    .bump_release(change_type.unwrap_or("patch"))?;
+                version.dev = Some(1);
+                Ok(version)
+            }
+        }
+    }
+
+    // Si la versión actual es `1.0rc1.dev2`, el siguiente paso es la propia `1.0rc1`.
+    fn next_counter(&self, n: u64) -> u64 {
+        if self.dev.is_some() { n } else { n + 1 }
+    }
+
+    fn bump_release(&self, change_type: &str) -> Result<Self, String> {
+        let index = release_index(change_type)?;
+        let mut release = self.release.clone();
+        if release.len() <= index {
+            release.resize(index + 1, 0);
+        }
+        let lower_are_zero = release.iter().skip(index + 1).all(|n| *n == 0);
+        // Igual que en SemVer: `1.4.0rc1` + minor publica `1.4.0`.
+        if !(self.is_prerelease() && lower_are_zero) {
+            release[index] += 1;
+        }
+        release.iter_mut().skip(index + 1).for_each(|n| *n = 0);
+        Ok(Self::from_release(self.epoch, release))
+    }
+
+    /// Calcula la versión anterior decrementando un segmento de release.
+    pub fn decrement(&self, change_type: &str) -> Result<Self, String> {
+        let index = release_index(change_type)?;
+        let mut release = self.release.clone();
+        if release.len() <= index {
+            release.resize(index + 1, 0);
+        }
+        if release[index] == 0 {
+            return Err(format!("Cannot downgrade {} 0 of version {}", change_type, self));
+        }
+        release[index] -= 1;
+        release.iter_mut().skip(index + 1).for_each(|n| *n = 0);
+        Ok(Self::from_release(self.epoch, release))
+    }
+}
+
+fn release_index(change_type: &str) -> Result<usize, String> {
+    match change_type {
+        "major" => Ok(0),
+        "minor" => Ok(1),
+        "patch" => Ok(2),
+        _ => Err(format!("Unknown change type: {}", change_type)),
+    }
+}
+
+impl FromStr for Pep440Version {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let re = Regex::new(PEP440_PATTERN).unwrap();
+        let captures = re
+            .captures(s)
+            .ok_or_else(|| format!("Invalid PEP 440 version: {}", s))?;
+        let number = |name: &str| -> Result<Option<u64>, String> {
+            captures
+                .name(name)
+                .map(|m| {
+                    m.as_str()
+                        .parse::<u64>()
+                        .map_err(|_| format!("Number '{}' is too large in version {}", m.as_str(), s))
+                })
+                .transpose()
+        };
+
+        let release = captures["release"]
+            .split('.')
+            .map(|n| n.parse::<u64>().map_err(|_| format!("Invalid release segment in version {}", s)))
+            .collect::<Result<Vec<_>, _>>()?;
+        let pre = match captures.name("pre_l") {
+            Some(label) => Some((PreKind::parse(label.as_str())?, number("pre_n")?.unwrap_or(0))),
+            None => None,
+        };
+        let post = if captures.name("post").is_some() {
+            Some(number("post_n1")?.or(number("post_n2")?).unwrap_or(0))
+        } else {
+            None
+        };
+        let dev = if captures.name("dev").is_some() {
+            Some(number("dev_n")?.unwrap_or(0))
+        } else {
+            None
+        };
+        let local = captures
+            .name("local")
+            .map(|m| m.as_str().to_ascii_lowercase().replace(['-', '_'], "."));
+
+        Ok(Self {
+            epoch: number("epoch")?.unwrap_or(0),
+            release,
+            pre,
+            post,
+            dev,
+            local,
+        })
+    }
+}
+
+impl fmt::Display for Pep440Version {
+    /// Muestra la forma normalizada de la versión.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.epoch > 0 {
+            write!(f, "{}!", self.epoch)?;
+        }
+        let release: Vec<String> = self.release.iter().map(|n| n.to_string()).collect();
+        write!(f, "{}", release.join("."))?;
+        if let Some((kind, n)) = self.pre {
+            write!(f, "{}{}", kind, n)?;
+        }
+        if let Some(n) = self.post {
+            write!(f, ".post{}", n)?;
+        }
+        if let Some(n) = self.dev {
+            write!(f, ".dev{}", n)?;
+        }
+        if let Some(local) = &self.local {
+            write!(f, "+{}", local)?;
+        }
+        Ok(())
+    }
+}
+
+impl PartialOrd for Pep440Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Pep440Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.sort_key()
+            .cmp(&other.sort_key())
+            .then_with(|| self.local.cmp(&other.local))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bump(version: &str, change_type: Option<&str>, change: PreReleaseChange) -> String {
+        version.parse::<Pep440Version>().unwrap().bump(change_type, change).unwrap().to_string()
+    }
+
+    #[test]
+    fn sort_key_follows_pep440_example() {
+        let chain = [
+            "1.0.dev456",
+            "1.0a1",
+            "1.0a2.dev456",
+            "1.0a12.dev456",
+            "1.0a12",
+            "1.0b1.dev456",
+            "1.0b2",
+            "1.0b2.post345.dev456",
+            "1.0b2.post345",
+            "1.0rc1.dev456",
+            "1.0rc1",
+            "1.0",
+            "1.0+abc.5",
+            "1.0.post456.dev34",
+            "1.0.post456",
+            "1.0.15",
+            "1.1.dev1",
+            "1!0.1",
+        ];
+        for pair in chain.windows(2) {
+            let (a, b) = (pair[0].parse::<Pep440Version>().unwrap(), pair[1].parse::<Pep440Version>().unwrap());
+            assert!(a < b, "{} < {}", pair[0], pair[1]);
+        }
+        assert_eq!(
+            "1.0".parse::<Pep440Version>().unwrap().cmp(&"1.0.0".parse().unwrap()),
+            Ordering::Equal
+        );
+    }
+
+    #[test]
+    fn parse_normalizes_spellings() {
+        let normalized = |s: &str| s.parse::<Pep440Version>().unwrap().to_string();
+        assert_eq!(normalized("1.0-ALPHA_1"), "1.0a1");
+        assert_eq!(normalized("v1.0c2"), "1.0rc2");
+        assert_eq!(normalized("1.0-1"), "1.0.post1");
+        assert_eq!(normalized("1.0.dev"), "1.0.dev0");
+        assert!("1.0.0-foo".parse::<Pep440Version>().is_err());
+    }
+
+    #[test]
+    fn dev_on_prerelease_targets_next_prerelease() {
+        assert_eq!(bump("1.4.0a1", None, PreReleaseChange::Dev), "1.4.0a2.dev1");
+        assert_eq!(bump("1.4.0a2.dev1", None, PreReleaseChange::Dev), "1.4.0a2.dev2");
+        assert_eq!(bump("1.4.0", None, PreReleaseChange::Dev), "1.4.1.dev1");
+        assert_eq!(bump("1.4.0a1", Some("minor"), PreReleaseChange::Dev), "1.5.0.dev1");
+    }
+}
@@ -0,0 +1,151 @@
+use serde::Serialize;
+use std::{fmt, process};
+use tracing::error;
+
+use crate::cli::OutputFormat;
+
+// =============================================================================================
+// INFORME DE RESULTADOS (SALIDA HUMANA O JSON)
+// =============================================================================================
+
+/// Estado de un archivo: `ok`, `updated`, `unchanged`, `restored` o `error` al cambiar de
+/// versión; `missing`, `mismatched` y `ambiguous` en `check`; `detected` y `skipped` en `init`.
+#[derive(Serialize)]
+pub struct FileReport {
+    pub file: String,
+    pub status: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub matches: Option<usize>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub found: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub lines: Vec<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+/// Error con un código estable (`config_error`, `simulation_failed`...) para los scripts.
+#[derive(Serialize)]
+pub struct ReportError {
+    pub code: &'static str,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+}
+
+/// Resultado de un comando. En formato humano cada línea se imprime en cuanto se produce;
+/// en JSON todo se acumula y se emite como un único documento al terminar.
+#[derive(Serialize)]
+pub struct Report {
+    #[serde(skip)]
+    format: OutputFormat,
+    command: &'static str,
+    success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub current_version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new_version: Option<String>,
+    // Origen de la versión actual (`config` o `git`) y etiqueta de la que se ha leído.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_tag: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub change_type: Option<&'static str>,
+    // Mensaje del commit y etiqueta creados tras el cambio de versión.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+    files: Vec<FileReport>,
+    warnings: Vec<String>,
+    errors: Vec<ReportError>,
+}
+
+impl Report {
+    pub fn new(format: OutputFormat, command: &'static str) -> Self {
+        Self {
+            format,
+            command,
+            success: true,
+            current_version: None,
+            new_version: None,
+            source: None,
+            source_tag: None,
+            change_type: None,
+            commit: None,
+            tag: None,
+            files: Vec::new(),
+            warnings: Vec::new(),
+            errors: Vec::new(),
+        }
+    }
+
+    pub fn is_json(&self) -> bool {
+        self.format == OutputFormat::Json
+    }
+
+    /// Imprime una línea del formato humano; en JSON se omite.
+    pub fn print(&self, line: impl fmt::Display) {
+        if !self.is_json() {
+            println!("{}", line);
+        }
+    }
+
+    pub fn warn(&mut self, message: String) {
+        self.print(format_args!("⚠️  Warning: {}", message));
+        self.warnings.push(message);
+    }
+
+    /// Añade un archivo al informe y lo devuelve para completar sus detalles.
+    pub fn file(&mut self, file: impl Into<String>, status: &'static str) -> &mut FileReport {
+        self.files.push(FileReport {
+            file: file.into(),
+            status,
+            matches: None,
+            found: Vec::new(),
+            lines: Vec::new(),
+            message: None,
+        });
+        self.files.last_mut().unwrap()
+    }
+
+    pub fn set_status(&mut self, file: &str, status: &'static str) {
+        for report in self.files.iter_mut().filter(|report| report.file == file) {
+            report.status = status;
+        }
+    }
+
+    /// Registra un error del comando y lo muestra en el log.
+    pub fn fail(&mut self, code: &'static str, message: impl Into<String>) {
+        let message = message.into();
+        error!("{}", message);
+        self.errors.push(ReportError { code, message, file: None });
+    }
+
+    /// Registra un error sin mostrarlo en el log, porque la salida humana ya lo refleja
+    /// (por ejemplo, en la línea de estado del archivo).
+    pub fn record(&mut self, code: &'static str, file: Option<&str>, message: impl Into<String>) {
+        self.errors.push(ReportError {
+            code,
+            message: message.into(),
+            file: file.map(str::to_string),
+        });
+    }
+
+    pub fn has_errors(&self) -> bool {
+        !self.errors.is_empty()
+    }
+
+    /// Emite el documento JSON (si procede) y termina con código 1 si ha habido errores.
+    pub fn finish(mut self) -> ! {
+        self.success = self.errors.is_empty();
+        if self.is_json() {
+            match serde_json::to_string_pretty(&self) {
+                Ok(document) => println!("{}", document),
+                Err(e) => error!("Failed to serialize the report: {}", e),
+            }
+        }
+        process::exit(if self.success { 0 } else { 1 })
+    }
+}
@@ -0,0 +1,768 @@
+use std::io;
+use toml_edit::{DocumentMut, Item};
+
+use crate::utils::SimulatedFile;
+
+// =============================================================================================
+// REEMPLAZOS ESTRUCTURADOS (por ruta de claves)
+// =============================================================================================
+
+/// Formato de archivo de una regla estructurada (`toml_path`, `json_path`, `yaml_path`,
+/// `xml_path`).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StructuredFormat {
+    Toml,
+    Json,
+    Yaml,
+    Xml,
+}
+
+/// Simula el cambio de versión en la clave `key_path` de un archivo estructurado.
+pub fn simulate_structured_replacement(
+    format: StructuredFormat,
+    path: &str,
+    content: String,
+    key_path: &str,
+    current_version: &str,
+    new_version: &str,
+) -> Result<SimulatedFile, io::Error> {
+    match format {
+        StructuredFormat::Toml => simulate_toml_replacement(path, content, key_path, current_version, new_version),
+        StructuredFormat::Json => simulate_json_replacement(path, content, key_path, current_version, new_version),
+        StructuredFormat::Yaml => simulate_yaml_replacement(path, content, key_path, current_version, new_version),
+        StructuredFormat::Xml => simulate_xml_replacement(path, content, key_path, current_version, new_version),
+    }
+}
+
+/// Lee el valor de `key_path` sin modificar nada (para `check`). Devuelve `None` si la clave
+/// no existe; los valores que no son cadenas se devuelven tal y como aparecen en el archivo.
+pub fn read_structured_value(
+    format: StructuredFormat,
+    path: &str,
+    content: &str,
+    key_path: &str,
+) -> Result<Option<String>, io::Error> {
+    match format {
+        StructuredFormat::Toml => {
+            let segments = split_key_path(key_path)?;
+            let mut document = content
+                .parse::<DocumentMut>()
+                .map_err(|e| invalid_data(format!("File '{}' is not valid TOML: {}", path, e)))?;
+            Ok(toml_item_mut(&mut document, &segments).map(|item| match item.as_str() {
+                Some(value) => value.to_string(),
+                None => item.to_string().trim().to_string(),
+            }))
+        }
+        StructuredFormat::Json => {
+            let segments = split_key_path(key_path)?;
+            let span = json_value_span(content, &segments)
+                .map_err(|e| invalid_data(format!("File '{}' is not valid JSON: {}", path, e)))?;
+            Ok(span.map(|(start, end)| {
+                let raw = &content[start..end];
+                serde_json::from_str::<String>(raw).unwrap_or_else(|_| raw.to_string())
+            }))
+        }
+        StructuredFormat::Yaml => {
+            let segments = split_key_path(key_path)?;
+            Ok(yaml_value_span(content, &segments).map(|(start, end)| unquote(&content[start..end]).to_string()))
+        }
+        StructuredFormat::Xml => XmlPath::parse(key_path)?
+            .value_span(content)
+            .map(|span| span.map(|(start, end)| content[start..end].to_string()))
+            .map_err(|e| invalid_data(format!("File '{}' is not valid XML: {}", path, e))),
+    }
+}
+
+pub(crate) fn invalid_data(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+fn not_found(key_path: &str, path: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::NotFound,
+        format!("Key '{}' NOT found in '{}'.", key_path, path),
+    )
+}
+
+/// Divide una ruta de claves (`package.version`, `.appVersion`) en segmentos.
+/// Los segmentos numéricos indexan arrays (`workspace.members.0`).
+fn split_key_path(key_path: &str) -> Result<Vec<&str>, io::Error> {
+    let trimmed = key_path.trim();
+    let segments: Vec<&str> = trimmed
+        .strip_prefix('.')
+        .unwrap_or(trimmed)
+        .split('.')
+        .map(str::trim)
+        .collect();
+    if segments.iter().any(|segment| segment.is_empty()) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("Invalid key path '{}'", key_path),
+        ));
+    }
+    Ok(segments)
+}
+
+// =============================================================================================
+// TOML
+// =============================================================================================
+
+// `Item::get_mut` crea entradas vacías para las claves que no existen; se tratan como ausentes.
+fn toml_item_mut<'a>(document: &'a mut DocumentMut, segments: &[&str]) -> Option<&'a mut Item> {
+    segments
+        .iter()
+        .try_fold(document.as_item_mut(), |item, segment| match segment.parse::<usize>() {
+            Ok(index) if item.is_array() => item.get_mut(index),
+            _ => item.get_mut(*segment),
+        })
+        .filter(|item| !item.is_none())
+}
+
+fn toml_string_at(document: &mut DocumentMut, segments: &[&str]) -> Option<String> {
+    toml_item_mut(document, segments)?
+        .as_str()
+        .map(str::to_string)
+}
+
+/// Sustituye el valor de `key_path` en un documento TOML conservando comentarios, orden y
+/// espacios. El valor debe ser una cadena igual a `current_version`.
+fn simulate_toml_replacement(
+    path: &str,
+    content: String,
+    key_path: &str,
+    current_version: &str,
+    new_version: &str,
+) -> Result<SimulatedFile, io::Error> {
+    let segments = split_key_path(key_path)?;
+    let mut document = content
+        .parse::<DocumentMut>()
+        .map_err(|e| invalid_data(format!("File '{}' is not valid TOML: {}", path, e)))?;
+
+    let item = toml_item_mut(&mut document, &segments).ok_or_else(|| not_found(key_path, path))?;
+    let value = item
+        .as_value_mut()
+        .filter(|value| value.is_str())
+        .ok_or_else(|| invalid_data(format!("Key '{}' in '{}' is not a string", key_path, path)))?;
+    check_current_value(path, key_path, value.as_str(), current_version)?;
+
+    // Se conservan los espacios y comentarios que rodean al valor.
+    let decor = value.decor().clone();
+    *value = new_version.into();
+    *value.decor_mut() = decor;
+
+    let mut modified = document.to_string();
+    // Verificación: el documento resultante debe contener la nueva versión en la clave.
+    let mut verified = modified
+        .parse::<DocumentMut>()
+        .map_err(|e| invalid_data(format!("Simulated TOML for '{}' is invalid: {}", path, e)))?;
+    if toml_string_at(&mut verified, &segments).as_deref() != Some(new_version) {
+        return Err(invalid_data(format!(
+            "Key '{}' in '{}' does not hold {} after simulation.",
+            key_path, path, new_version
+        )));
+    }
+    if content.ends_with('\n') && !modified.ends_with('\n') {
+        modified.push('\n');
+    }
+
+    Ok(SimulatedFile {
+        path: path.to_string(),
+        original: content,
+        modified,
+        matches: 1,
+    })
+}
+
+// =============================================================================================
+// EDICIÓN POR RANGO (JSON y YAML)
+// =============================================================================================
+
+/// Comprueba que el valor actual de la clave es una cadena igual a `current_version`.
+fn check_current_value(
+    path: &str,
+    key_path: &str,
+    value: Option<&str>,
+    current_version: &str,
+) -> Result<(), io::Error> {
+    let value = value.ok_or_else(|| invalid_data(format!("Key '{}' in '{}' is not a string", key_path, path)))?;
+    if value != current_version {
+        return Err(invalid_data(format!(
+            "Key '{}' in '{}' is '{}', expected the current version {}",
+            key_path, path, value, current_version
+        )));
+    }
+    Ok(())
+}
+
+/// Sustituye el texto de `span` (el valor en bruto de la clave) por `new_raw`, dejando el
+/// resto del archivo byte a byte, y comprueba con `read_back` que el valor leído de nuevo
+/// es la nueva versión.
+fn replace_span(
+    path: &str,
+    content: String,
+    key_path: &str,
+    span: (usize, usize),
+    new_raw: &str,
+    new_version: &str,
+    read_back: impl Fn(&str) -> Result<Option<String>, String>,
+) -> Result<SimulatedFile, io::Error> {
+    let modified = format!("{}{}{}", &content[..span.0], new_raw, &content[span.1..]);
+    let value = read_back(&modified)
+        .map_err(|e| invalid_data(format!("Simulated content for '{}' is invalid: {}", path, e)))?;
+    if value.as_deref() != Some(new_version) {
+        return Err(invalid_data(format!(
+            "Key '{}' in '{}' does not hold {} after simulation.",
+            key_path, path, new_version
+        )));
+    }
+
+    Ok(SimulatedFile {
+        path: path.to_string(),
+        original: content,
+        modified,
+        matches: 1,
+    })
+}
+
+// =============================================================================================
+// JSON
+// =============================================================================================
+
+/// Recorre el texto JSON sin reconstruirlo para localizar el rango exacto de un valor.
+struct JsonScanner<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+impl<'a> JsonScanner<'a> {
+    fn peek(&self) -> Option<u8> {
+        self.text.as_bytes().get(self.pos).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(|b| b.is_ascii_whitespace()) {
+            self.pos += 1;
+        }
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<(), String> {
+        self.skip_whitespace();
+        if self.peek() == Some(byte) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(format!("expected '{}' at byte {}", byte as char, self.pos))
+        }
+    }
+
+    fn string(&mut self) -> Result<&'a str, String> {
+        let start = self.pos;
+        self.expect(b'"')?;
+        while let Some(byte) = self.peek() {
+            self.pos += 1;
+            match byte {
+                b'\\' => self.pos += 1,
+                b'"' => return Ok(&self.text[start..self.pos]),
+                _ => {}
+            }
+        }
+        Err("unterminated string".to_string())
+    }
+
+    fn skip_value(&mut self) -> Result<(), String> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(b'"') => self.string().map(|_| ()),
+            Some(open @ (b'{' | b'[')) => {
+                let close = if open == b'{' { b'}' } else { b']' };
+                self.pos += 1;
+                self.skip_whitespace();
+                if self.peek() == Some(close) {
+                    self.pos += 1;
+                    return Ok(());
+                }
+                loop {
+                    if open == b'{' {
+                        self.skip_whitespace();
+                        self.string()?;
+                        self.expect(b':')?;
+                    }
+                    self.skip_value()?;
+                    self.skip_whitespace();
+                    match self.peek() {
+                        Some(b',') => self.pos += 1,
+                        Some(byte) if byte == close => {
+                            self.pos += 1;
+                            return Ok(());
+                        }
+                        _ => return Err(format!("unexpected character at byte {}", self.pos)),
+                    }
+                }
+            }
+            Some(_) => {
+                while self
+                    .peek()
+                    .is_some_and(|b| !b.is_ascii_whitespace() && !matches!(b, b',' | b'}' | b']'))
+                {
+                    self.pos += 1;
+                }
+                Ok(())
+            }
+            None => Err("unexpected end of input".to_string()),
+        }
+    }
+
+    /// Devuelve el rango del valor en `segments`, o `None` si la clave no existe.
+    fn find(&mut self, segments: &[&str]) -> Result<Option<(usize, usize)>, String> {
+        self.skip_whitespace();
+        let Some((segment, rest)) = segments.split_first() else {
+            let start = self.pos;
+            self.skip_value()?;
+            return Ok(Some((start, self.pos)));
+        };
+
+        match self.peek() {
+            Some(b'{') => {
+                self.pos += 1;
+                loop {
+                    self.skip_whitespace();
+                    if self.peek() == Some(b'}') {
+                        return Ok(None);
+                    }
+                    let key: String = serde_json::from_str(self.string()?).map_err(|e| e.to_string())?;
+                    self.expect(b':')?;
+                    if key == *segment {
+                        return self.find(rest);
+                    }
+                    self.skip_value()?;
+                    self.skip_whitespace();
+                    if self.peek() == Some(b',') {
+                        self.pos += 1;
+                    }
+                }
+            }
+            Some(b'[') => {
+                let Ok(index) = segment.parse::<usize>() else {
+                    return Ok(None);
+                };
+                self.pos += 1;
+                for _ in 0..index {
+                    self.skip_whitespace();
+                    if self.peek() == Some(b']') {
+                        return Ok(None);
+                    }
+                    self.skip_value()?;
+                    self.expect(b',').map_err(|_| "index out of bounds".to_string())?;
+                }
+                self.skip_whitespace();
+                if self.peek() == Some(b']') {
+                    return Ok(None);
+                }
+                self.find(rest)
+            }
+            _ => Ok(None),
+        }
+    }
+}
+
+/// Localiza el rango en bruto del valor JSON en `segments`, o `None` si la clave no existe.
+pub fn json_value_span(content: &str, segments: &[&str]) -> Result<Option<(usize, usize)>, String> {
+    JsonScanner { text: content, pos: 0 }.find(segments)
+}
+
+fn json_pointer(segments: &[&str]) -> String {
+    segments
+        .iter()
+        .map(|segment| format!("/{}", segment.replace('~', "~0").replace('/', "~1")))
+        .collect()
+}
+
+/// Sustituye el valor de `key_path` en un documento JSON conservando la indentación, el
+/// orden de las claves y el salto de línea final.
+fn simulate_json_replacement(
+    path: &str,
+    content: String,
+    key_path: &str,
+    current_version: &str,
+    new_version: &str,
+) -> Result<SimulatedFile, io::Error> {
+    let segments = split_key_path(key_path)?;
+    serde_json::from_str::<serde_json::Value>(&content)
+        .map_err(|e| invalid_data(format!("File '{}' is not valid JSON: {}", path, e)))?;
+
+    let span = json_value_span(&content, &segments)
+        .map_err(|e| invalid_data(format!("Error reading JSON in '{}': {}", path, e)))?
+        .ok_or_else(|| not_found(key_path, path))?;
+    let current_value = serde_json::from_str::<String>(&content[span.0..span.1]).ok();
+    check_current_value(path, key_path, current_value.as_deref(), current_version)?;
+
+    let new_raw = serde_json::to_string(new_version).map_err(|e| invalid_data(e.to_string()))?;
+    let pointer = json_pointer(&segments);
+    replace_span(path, content, key_path, span, &new_raw, new_version, |modified| {
+        let document: serde_json::Value = serde_json::from_str(modified).map_err(|e| e.to_string())?;
+        Ok(document.pointer(&pointer).and_then(|v| v.as_str()).map(str::to_string))
+    })
+}
+
+// =============================================================================================
+// YAML
+// =============================================================================================
+
+/// Localiza el rango del valor escalar de `segments` en mapas YAML de estilo bloque
+/// (`clave: valor` con indentación), sin incluir comentarios finales.
+fn yaml_value_span(content: &str, segments: &[&str]) -> Option<(usize, usize)> {
+    let mut depth = 0;
+    let mut parent_indent: Option<usize> = None;
+    let mut level_indent: Option<usize> = None;
+    let mut offset = 0;
+
+    for line in content.split_inclusive('\n') {
+        let line_start = offset;
+        offset += line.len();
+
+        let text = line.trim_end_matches(['\n', '\r']);
+        let trimmed = text.trim_start();
+        if trimmed.is_empty() || trimmed.starts_with('#') || trimmed.starts_with("---") {
+            continue;
+        }
+        let indent = text.len() - trimmed.len();
+        if parent_indent.is_some_and(|parent| indent <= parent) {
+            return None;
+        }
+        if *level_indent.get_or_insert(indent) != indent {
+            continue;
+        }
+
+        let Some((key, value)) = trimmed.split_once(':') else {
+            continue;
+        };
+        if !(value.is_empty() || value.starts_with([' ', '\t'])) {
+            continue;
+        }
+        if key.trim().trim_matches(['"', '\'']) != segments[depth] {
+            continue;
+        }
+
+        if depth + 1 < segments.len() {
+            depth += 1;
+            parent_indent = Some(indent);
+            level_indent = None;
+            continue;
+        }
+
+        let value = value.trim_start();
+        let start = line_start + text.len() - value.len();
+        let end = match value.chars().next() {
+            Some(quote @ ('"' | '\'')) => value[1..].find(quote).map(|i| start + i + 2)?,
+            Some(_) => start + value.find(" #").unwrap_or(value.len()),
+            None => return None,
+        };
+        let raw = content[start..end].trim_end();
+        return Some((start, start + raw.len()));
+    }
+
+    None
+}
+
+fn unquote(raw: &str) -> &str {
+    raw.strip_prefix('"')
+        .and_then(|r| r.strip_suffix('"'))
+        .or_else(|| raw.strip_prefix('\'').and_then(|r| r.strip_suffix('\'')))
+        .unwrap_or(raw)
+}
+
+/// Sustituye el valor de `key_path` en un documento YAML conservando la indentación, el
+/// orden, los comentarios y el estilo de comillas del valor.
+fn simulate_yaml_replacement(
+    path: &str,
+    content: String,
+    key_path: &str,
+    current_version: &str,
+    new_version: &str,
+) -> Result<SimulatedFile, io::Error> {
+    let segments = split_key_path(key_path)?;
+    serde_yaml::from_str::<serde_yaml::Value>(&content)
+        .map_err(|e| invalid_data(format!("File '{}' is not valid YAML: {}", path, e)))?;
+
+    let span = yaml_value_span(&content, &segments).ok_or_else(|| not_found(key_path, path))?;
+    let raw = &content[span.0..span.1];
+    check_current_value(path, key_path, Some(unquote(raw)), current_version)?;
+
+    // Un escalar sin comillas que YAML leería como número (`1.10`) se escribe entre comillas.
+    let new_raw = match raw.chars().next() {
+        Some(quote @ ('"' | '\'')) => format!("{}{}{}", quote, new_version, quote),
+        _ if serde_yaml::from_str::<serde_yaml::Value>(new_version).ok()
+            == Some(serde_yaml::Value::String(new_version.to_string())) =>
+        {
+            new_version.to_string()
+        }
+        _ => format!("\"{}\"", new_version),
+    };
+    // Verificación independiente del localizador: se analiza el resultado con serde_yaml.
+    replace_span(path, content, key_path, span, &new_raw, new_version, |modified| {
+        let document: serde_yaml::Value = serde_yaml::from_str(modified).map_err(|e| e.to_string())?;
+        Ok(yaml_scalar(&document, &segments))
+    })
+}
+
+/// Valor escalar en `segments` de un documento YAML ya analizado (claves de mapas o índices
+/// de secuencias), como texto.
+fn yaml_scalar(document: &serde_yaml::Value, segments: &[&str]) -> Option<String> {
+    let value = segments.iter().try_fold(document, |value, segment| match value {
+        serde_yaml::Value::Sequence(items) => items.get(segment.parse::<usize>().ok()?),
+        _ => value.get(*segment),
+    })?;
+    match value {
+        serde_yaml::Value::String(value) => Some(value.clone()),
+        serde_yaml::Value::Number(value) => Some(value.to_string()),
+        serde_yaml::Value::Bool(value) => Some(value.to_string()),
+        _ => None,
+    }
+}
+
+// =============================================================================================
+// XML
+// =============================================================================================
+
+/// Paso de una ruta XML: nombre local del elemento y posición opcional (desde 1).
+struct XmlStep<'a> {
+    name: &'a str,
+    position: Option<usize>,
+}
+
+/// Ruta XML al estilo XPath: `/project/version`, `PropertyGroup/Version` (relativa al
+/// elemento raíz) o `releases/release[1]/@version` para atributos.
+struct XmlPath<'a> {
+    absolute: bool,
+    steps: Vec<XmlStep<'a>>,
+    attribute: Option<&'a str>,
+}
+
+impl<'a> XmlPath<'a> {
+    fn parse(xml_path: &'a str) -> Result<Self, io::Error> {
+        let invalid = || {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Invalid XML path '{}'", xml_path),
+            )
+        };
+        let trimmed = xml_path.trim();
+        let absolute = trimmed.starts_with('/');
+        let mut segments: Vec<&str> = trimmed.trim_start_matches('/').split('/').collect();
+        let attribute = match segments.last().and_then(|last| last.strip_prefix('@')) {
+            Some(attribute) => {
+                segments.pop();
+                Some(attribute)
+            }
+            None => None,
+        };
+
+        let steps = segments
+            .into_iter()
+            .map(|segment| match segment.split_once('[') {
+                Some((name, position)) => {
+                    let position = position
+                        .strip_suffix(']')
+                        .and_then(|n| n.parse::<usize>().ok())
+                        .filter(|n| *n > 0)
+                        .ok_or_else(invalid)?;
+                    Ok(XmlStep { name, position: Some(position) })
+                }
+                None => Ok(XmlStep { name: segment, position: None }),
+            })
+            .collect::<Result<Vec<_>, io::Error>>()?;
+        if steps.iter().any(|step| step.name.is_empty()) || attribute == Some("") {
+            return Err(invalid());
+        }
+        Ok(Self { absolute, steps, attribute })
+    }
+
+    // Primer descendiente (en orden del documento) que sigue todos los pasos.
+    fn find<'d, 'i>(node: roxmltree::Node<'d, 'i>, steps: &[XmlStep]) -> Option<roxmltree::Node<'d, 'i>> {
+        let Some((step, rest)) = steps.split_first() else {
+            return Some(node);
+        };
+        node.children()
+            .filter(|child| child.is_element() && child.tag_name().name() == step.name)
+            .enumerate()
+            .filter(|(i, _)| step.position.is_none_or(|position| position == i + 1))
+            .find_map(|(_, child)| Self::find(child, rest))
+    }
+
+    /// Devuelve el rango en bruto del texto o del atributo, sin los espacios que lo rodean.
+    fn value_span(&self, content: &str) -> Result<Option<(usize, usize)>, String> {
+        let options = roxmltree::ParsingOptions {
+            allow_dtd: true,
+            ..Default::default()
+        };
+        let document = roxmltree::Document::parse_with_options(content, options).map_err(|e| e.to_string())?;
+        let root = document.root_element();
+
+        let steps = if self.absolute {
+            match self.steps.split_first() {
+                Some((first, rest)) if first.name == root.tag_name().name() => rest,
+                _ => return Ok(None),
+            }
+        } else {
+            &self.steps[..]
+        };
+        let Some(element) = Self::find(root, steps) else {
+            return Ok(None);
+        };
+
+        let range = match self.attribute {
+            Some(name) => element
+                .attributes()
+                .find(|attribute| attribute.name() == name)
+                .map(|attribute| attribute.range_value()),
+            None => element
+                .first_child()
+                .filter(|child| child.is_text())
+                .map(|text| text.range()),
+        };
+        Ok(range.map(|range| {
+            let raw = &content[range.clone()];
+            let start = range.start + (raw.len() - raw.trim_start().len());
+            (start, start + raw.trim().len())
+        }))
+    }
+}
+
+fn escape_xml(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// Sustituye el texto de un elemento o el valor de un atributo en un documento XML
+/// (`pom.xml`, `.csproj`, metadatos AppStream) dejando el resto del archivo byte a byte.
+fn simulate_xml_replacement(
+    path: &str,
+    content: String,
+    xml_path: &str,
+    current_version: &str,
+    new_version: &str,
+) -> Result<SimulatedFile, io::Error> {
+    let xml = XmlPath::parse(xml_path)?;
+    let span = xml
+        .value_span(&content)
+        .map_err(|e| invalid_data(format!("File '{}' is not valid XML: {}", path, e)))?
+        .ok_or_else(|| not_found(xml_path, path))?;
+    check_current_value(path, xml_path, Some(&content[span.0..span.1]), current_version)?;
+
+    replace_span(path, content, xml_path, span, &escape_xml(new_version), new_version, |modified| {
+        Ok(xml
+            .value_span(modified)?
+            .map(|(start, end)| modified[start..end].to_string()))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edit(format: StructuredFormat, content: &str, key_path: &str) -> Result<String, io::Error> {
+        simulate_structured_replacement(format, "file", content.to_string(), key_path, "1.2.3", "1.3.0")
+            .map(|file| file.modified)
+    }
+
+    #[test]
+    fn toml_edits_only_the_addressed_key() {
+        let content = "[package]\nname = \"demo\"\nversion = \"1.2.3\"  # actual\n\n[dependencies]\nfoo = { version = \"1.2.3\" }\n";
+        let modified = edit(StructuredFormat::Toml, content, "package.version").unwrap();
+        assert_eq!(modified, content.replacen("\"1.2.3\"", "\"1.3.0\"", 1));
+    }
+
+    #[test]
+    fn toml_follows_nested_keys_and_array_indices() {
+        let content = "[tool.demo]\nreleases = [\"1.0.0\", \"1.2.3\"]\n";
+        let modified = edit(StructuredFormat::Toml, content, "tool.demo.releases.1").unwrap();
+        assert_eq!(modified, "[tool.demo]\nreleases = [\"1.0.0\", \"1.3.0\"]\n");
+    }
+
+    #[test]
+    fn toml_rejects_missing_keys_and_other_values() {
+        let content = "[package]\nversion = \"1.2.2\"\n";
+        let error = edit(StructuredFormat::Toml, content, "package.name").unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::NotFound);
+        let error = edit(StructuredFormat::Toml, content, "package.version").unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn json_follows_nested_keys_and_array_indices() {
+        let content = "{\n  \"name\": \"demo\",\n  \"meta\": { \"releases\": [ { \"version\": \"1.0.0\" }, { \"version\": \"1.2.3\" } ] }\n}\n";
+        let modified = edit(StructuredFormat::Json, content, "meta.releases.1.version").unwrap();
+        assert_eq!(modified, content.replace("\"1.2.3\"", "\"1.3.0\""));
+    }
+
+    #[test]
+    fn json_skips_escaped_strings() {
+        let content = "{\"note\": \"see \\\"version\\\": \\\"1.2.3\\\"\", \"ver\\u0073ion\": \"1.2.3\"}";
+        let modified = edit(StructuredFormat::Json, content, "version").unwrap();
+        assert_eq!(modified, "{\"note\": \"see \\\"version\\\": \\\"1.2.3\\\"\", \"ver\\u0073ion\": \"1.3.0\"}");
+    }
+
+    #[test]
+    fn json_reports_missing_keys() {
+        let error = edit(StructuredFormat::Json, "{\"name\": \"demo\"}", ".version").unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::NotFound);
+    }
+
+    #[test]
+    fn yaml_keeps_quote_style_and_comments() {
+        let content = "# version: 0.0.0\nname: demo\nversion: 1.2.3 # chart\nappVersion: \"1.2.3\"\nimage:\n  tag: '1.2.3'\n";
+        let modified = edit(StructuredFormat::Yaml, content, "version").unwrap();
+        assert_eq!(modified, content.replacen("1.2.3 # chart", "1.3.0 # chart", 1));
+        let modified = edit(StructuredFormat::Yaml, content, ".appVersion").unwrap();
+        assert_eq!(modified, content.replace("\"1.2.3\"", "\"1.3.0\""));
+        let modified = edit(StructuredFormat::Yaml, content, "image.tag").unwrap();
+        assert_eq!(modified, content.replace("'1.2.3'", "'1.3.0'"));
+    }
+
+    #[test]
+    fn yaml_quotes_versions_that_would_read_as_numbers() {
+        let content = "version: 1.9\n";
+        let file =
+            simulate_structured_replacement(StructuredFormat::Yaml, "file", content.to_string(), "version", "1.9", "1.10")
+                .unwrap();
+        assert_eq!(file.modified, "version: \"1.10\"\n");
+    }
+
+    #[test]
+    fn yaml_rejects_duplicate_and_missing_keys() {
+        let error = edit(StructuredFormat::Yaml, "version: 1.2.3\nversion: 1.2.3\n", "version").unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        let error = edit(StructuredFormat::Yaml, "image:\n  tag: 1.2.3\nversion: 1.2.3\n", "image.version").unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::NotFound);
+    }
+
+    #[test]
+    fn xml_matches_local_names_in_namespaced_documents() {
+        let content = "<?xml version=\"1.0\"?>\n<project xmlns=\"http://maven.apache.org/POM/4.0.0\">\n  <parent><version>1.0.0</version></parent>\n  <version> 1.2.3 </version>\n</project>\n";
+        let modified = edit(StructuredFormat::Xml, content, "/project/version").unwrap();
+        assert_eq!(modified, content.replace(" 1.2.3 ", " 1.3.0 "));
+
+        let content = "<p:Project xmlns:p=\"urn:demo\"><p:PropertyGroup><p:Version>1.2.3</p:Version></p:PropertyGroup></p:Project>";
+        let modified = edit(StructuredFormat::Xml, content, "PropertyGroup/Version").unwrap();
+        assert_eq!(modified, content.replace("1.2.3", "1.3.0"));
+    }
+
+    #[test]
+    fn xml_edits_attributes_by_position() {
+        let content = "<component>\n  <releases>\n    <release version=\"1.2.3\" date=\"2024-01-01\"/>\n    <release version='1.2.3'/>\n  </releases>\n</component>\n";
+        let modified = edit(StructuredFormat::Xml, content, "releases/release[2]/@version").unwrap();
+        assert_eq!(modified, content.replace("'1.2.3'", "'1.3.0'"));
+    }
+
+    #[test]
+    fn xml_reports_missing_elements() {
+        let error = edit(StructuredFormat::Xml, "<project><name>demo</name></project>", "/project/version").unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::NotFound);
+        let error = edit(StructuredFormat::Xml, "<project><version>1.2.3</version></project>", "/pom/version").unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::NotFound);
+    }
+}
@@ -0,0 +1,101 @@
+use chrono::Local;
+use regex::{Captures, Regex};
+use std::{collections::HashMap, env, fmt::Write};
+
+use crate::config::Config;
+use crate::git;
+use crate::utils::version_components;
+
+// =============================================================================================
+// MOTOR DE PLANTILLAS
+// =============================================================================================
+
+/// Variables disponibles en patrones, plantillas de reemplazo, mensajes y etiquetas:
+///
+/// - `{{current_version}}` y `{{new_version}}`.
+/// - Componentes de la nueva versión (`{{major}}`, `{{minor}}`, `{{patch}}`, `{{prerelease}}`,
+///   `{{build}}`...) y de la actual con prefijo `current_` (`{{current_major}}`...).
+/// - `{{now}}` o `{{now:FORMATO}}` con la fecha local en formato `strftime`.
+/// - `{{env:NOMBRE}}` con el valor de una variable de entorno.
+/// - `{{git_sha}}` (SHA corto de HEAD) y `{{git_branch}}` dentro de un repositorio git.
+pub struct TemplateContext {
+    variables: HashMap<String, String>,
+}
+
+impl TemplateContext {
+    pub async fn new(config: &Config, current_version: &str, new_version: &str) -> Self {
+        let mut variables = HashMap::new();
+        variables.insert("current_version".to_string(), current_version.to_string());
+        variables.insert("new_version".to_string(), new_version.to_string());
+
+        for (name, value) in version_components(config, new_version) {
+            variables.insert(name, value);
+        }
+        for (name, value) in version_components(config, current_version) {
+            variables.insert(format!("current_{}", name), value);
+        }
+
+        if git::is_repository().await {
+            if let Ok(sha) = git::short_sha().await {
+                variables.insert("git_sha".to_string(), sha);
+            }
+            if let Ok(branch) = git::current_branch().await {
+                variables.insert("git_branch".to_string(), branch);
+            }
+        }
+
+        Self { variables }
+    }
+
+    fn resolve(&self, name: &str, argument: Option<&str>) -> Result<String, String> {
+        match (name, argument) {
+            ("now", format) => {
+                let mut now = String::new();
+                write!(now, "{}", Local::now().format(format.unwrap_or("%Y-%m-%d")))
+                    .map_err(|_| format!("Invalid date format '{}'", format.unwrap_or_default()))?;
+                Ok(now)
+            }
+            ("env", Some(variable)) => env::var(variable)
+                .map_err(|_| format!("Environment variable '{}' is not set", variable)),
+            (_, None) => self
+                .variables
+                .get(name)
+                .cloned()
+                .ok_or_else(|| format!("Unknown template variable '{{{{{}}}}}'", name)),
+            (_, Some(argument)) => Err(format!(
+                "Unknown template variable '{{{{{}:{}}}}}'",
+                name, argument
+            )),
+        }
+    }
+
+    fn render_with(&self, template: &str, transform: fn(&str) -> String) -> Result<String, String> {
+        let re = Regex::new(r"\{\{\s*(\w+)(?::([^}]*))?\s*\}\}").unwrap();
+        let mut error = None;
+        let rendered = re.replace_all(template, |captures: &Captures| {
+            let argument = captures.get(2).map(|m| m.as_str());
+            match self.resolve(&captures[1], argument) {
+                Ok(value) => transform(&value),
+                Err(e) => {
+                    error.get_or_insert(e);
+                    String::new()
+                }
+            }
+        });
+
+        match error {
+            Some(e) => Err(e),
+            None => Ok(rendered.into_owned()),
+        }
+    }
+
+    /// Sustituye las variables por su valor literal (reemplazos, mensajes, etiquetas).
+    pub fn render(&self, template: &str) -> Result<String, String> {
+        self.render_with(template, str::to_string)
+    }
+
+    /// Sustituye las variables escapando su valor para usarlo dentro de una RegEx de búsqueda.
+    pub fn render_pattern(&self, template: &str) -> Result<String, String> {
+        self.render_with(template, regex::escape)
+    }
+}
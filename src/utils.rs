@@ -1,10 +1,16 @@
+use chrono::NaiveDate;
 use regex::Regex;
-use std::{env, io, path::PathBuf};
+use std::{cmp::Ordering, env, io, path::PathBuf};
 use tokio::fs;
-use tracing::debug;
+use tracing::{debug, error};
 
-use crate::config;
-use crate::cli::VersionArgs;
+use crate::config::{Config, Replace, Scheme, VersionSource};
+use crate::cli::{PreReleaseArgs, VersionArgs};
+use crate::conventional::{AutoChange, infer_version_change};
+use crate::git;
+use crate::pep440::Pep440Version;
+use crate::report::Report;
+use crate::version::{Identifier, Version};
 
 const APP_NAME: &str = "vampus";
 
@@ -39,13 +45,79 @@ pub fn wrap_search_pattern(search_pattern: &str) -> String {
 // LÓGICA DE ARCHIVOS (TRANSACCIONAL)
 // =============================================================================================
 
-/// Simula el reemplazo con RegEx, verifica que el cambio se hizo, y devuelve el contenido modificado.
-pub async fn simulate_replacement(
+/// Cambio simulado sobre un archivo: se conserva el contenido original para poder
+/// restaurarlo si la transacción falla.
+pub struct SimulatedFile {
+    pub path: String,
+    pub original: String,
+    pub modified: String,
+    pub matches: usize,
+}
+
+/// Número de coincidencias que admite una regla: entre `min` y `max` (sin `max`, sin
+/// límite superior).
+#[derive(Debug, Clone, Copy)]
+pub struct MatchBounds {
+    pub min: usize,
+    pub max: Option<usize>,
+}
+
+impl MatchBounds {
+    pub fn contains(&self, matches: usize) -> bool {
+        matches >= self.min && self.max.is_none_or(|max| matches <= max)
+    }
+
+    pub fn describe(&self) -> String {
+        match self.max {
+            Some(max) if max == self.min => format!("exactly {}", max),
+            Some(max) if self.min == 0 => format!("at most {}", max),
+            Some(max) => format!("between {} and {}", self.min, max),
+            None => format!("at least {}", self.min),
+        }
+    }
+}
+
+/// Lee un archivo de texto y comprueba que su contenido es UTF-8 válido.
+pub async fn read_text_file(path: &str) -> Result<String, io::Error> {
+    let content_bytes = fs::read(path).await?;
+    String::from_utf8(content_bytes).map_err(|e| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "File '{}' does NOT contain valid UTF-8 text: {}",
+                path, e
+            ),
+        )
+    })
+}
+
+/// Agrupa los cambios simulados por archivo cuando varias reglas tocan el mismo: se
+/// conserva el contenido original del primero y el modificado del último.
+pub fn merge_simulated_files(files: Vec<SimulatedFile>) -> Vec<SimulatedFile> {
+    let mut merged: Vec<SimulatedFile> = Vec::new();
+    for simulated in files {
+        match merged.iter_mut().find(|file| file.path == simulated.path) {
+            Some(file) => {
+                file.modified = simulated.modified;
+                file.matches += simulated.matches;
+            }
+            None => merged.push(simulated),
+        }
+    }
+    merged
+}
+
+/// Simula el reemplazo con RegEx sobre `content`, verifica que el número de coincidencias
+/// está dentro de `bounds` y que el cambio se hizo, y devuelve el contenido original junto
+/// con el modificado.
+pub fn simulate_replacement(
     path: &str,
+    content: String,
     pattern_from: &str,
     replacement_to: &str,
     pattern_to: &str,
-) -> Result<String, io::Error> {
+    bounds: MatchBounds,
+) -> Result<SimulatedFile, io::Error> {
     // 1. Compilar la expresión regular de búsqueda (FROM).
     let re_from = Regex::new(pattern_from).map_err(|e| {
         io::Error::new(
@@ -62,20 +134,14 @@ pub async fn simulate_replacement(
         )
     })?;
 
-    // 3. Lectura y Conversión (I/O).
-    let content_bytes = fs::read(path).await?;
-    let content = String::from_utf8(content_bytes).map_err(|e| {
-        io::Error::new(
-            io::ErrorKind::InvalidData,
-            format!(
-                "File '{}' does NOT contain valid UTF-8 text: {}",
-                path, e
-            ),
-        )
-    })?;
-
-    // 4. Verificación de existencia (CRÍTICO): El patrón antiguo DEBE estar presente.
-    if !re_from.is_match(&content) {
+    // 3. Verificación de existencia (CRÍTICO): El patrón antiguo DEBE estar presente
+    //    tantas veces como declara la regla.
+    let lines: Vec<usize> = re_from
+        .find_iter(&content)
+        .map(|m| content[..m.start()].matches('\n').count() + 1)
+        .collect();
+    let matches = lines.len();
+    if matches == 0 && bounds.min > 0 {
         return Err(io::Error::new(
             io::ErrorKind::NotFound,
             format!(
@@ -84,14 +150,42 @@ pub async fn simulate_replacement(
             ),
         ));
     }
+    if !bounds.contains(matches) {
+        let lines: Vec<String> = lines.iter().map(|line| line.to_string()).collect();
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "Pattern '{}' matched {} time(s) in '{}' (lines {}), expected {}.",
+                pattern_from,
+                matches,
+                path,
+                lines.join(", "),
+                bounds.describe()
+            ),
+        ));
+    }
+    // Una regla opcional (`min: 0`) sin coincidencias deja el archivo intacto.
+    if matches == 0 {
+        return Ok(SimulatedFile {
+            path: path.to_string(),
+            modified: content.clone(),
+            original: content,
+            matches,
+        });
+    }
 
-    // 5. Reemplazo de la Cadena usando la RegEx (Simulación).
+    // 4. Reemplazo de la Cadena usando la RegEx (Simulación).
     let modified_content = re_from.replace_all(&content, replacement_to);
     debug!("Content modified simulated:\n{}", modified_content);
 
-    // 6. Verificación del Reemplazo (CRÍTICO): La nueva versión DEBE estar presente.
+    // 5. Verificación del Reemplazo (CRÍTICO): La nueva versión DEBE estar presente.
     if re_to.is_match(&modified_content) {
-        Ok(modified_content.into_owned()) // Devuelve el String modificado
+        Ok(SimulatedFile {
+            path: path.to_string(),
+            modified: modified_content.into_owned(),
+            original: content,
+            matches,
+        })
     } else {
         Err(io::Error::new(
             io::ErrorKind::InvalidData,
@@ -103,9 +197,43 @@ pub async fn simulate_replacement(
     }
 }
 
-/// Escribe el contenido pre-calculado en el archivo, reemplazando el contenido existente.
+/// Escribe el contenido pre-calculado en el archivo de forma atómica: primero en un
+/// archivo temporal hermano (con los mismos permisos) y después lo renombra sobre el original.
 pub async fn apply_replacement(path: &str, content: &str) -> Result<(), io::Error> {
-    fs::write(path, content.as_bytes()).await
+    let target = PathBuf::from(path);
+    let file_name = target
+        .file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, format!("Invalid file path '{}'", path)))?;
+    let temp = target.with_file_name(format!(".{}.{}.tmp", file_name.to_string_lossy(), APP_NAME));
+
+    fs::write(&temp, content.as_bytes()).await?;
+    if let Ok(metadata) = fs::metadata(&target).await {
+        fs::set_permissions(&temp, metadata.permissions()).await?;
+    }
+    if let Err(e) = fs::rename(&temp, &target).await {
+        let _ = fs::remove_file(&temp).await;
+        return Err(e);
+    }
+    Ok(())
+}
+
+/// Restaura el contenido original de los archivos ya escritos. Devuelve `false` si
+/// alguno no se ha podido restaurar.
+pub async fn rollback_replacements(report: &mut Report, files: &[&SimulatedFile]) -> bool {
+    let mut all_restored = true;
+    for file in files.iter().rev() {
+        match apply_replacement(&file.path, &file.original).await {
+            Ok(_) => {
+                report.print(format_args!("↩️  Restored: {}", file.path));
+                report.set_status(&file.path, "restored");
+            }
+            Err(e) => {
+                error!(File=%file.path, "CRITICAL ROLLBACK FAILURE: {}", e);
+                all_restored = false;
+            }
+        }
+    }
+    all_restored
 }
 
 
@@ -120,12 +248,14 @@ pub enum Operation {
 }
 
 /// Determina el tipo de cambio de versión basado en las flags mutuamente excluyentes.
-pub fn get_version_change(args: &VersionArgs) -> (&'static str, Operation) {
+pub fn get_version_change(args: &VersionArgs) -> (&str, Operation) {
     // Nota: Esta función ya no necesita el argumento 'operation' porque la operación
     // real se define en el match principal (Upgrade vs Downgrade).
 
     // Si la operación es explícita, el tipo de cambio se determina por la flag
-    if args.major {
+    if let Some(part) = &args.part {
+        (part.as_str(), Operation::Increment)
+    } else if args.major {
         ("major", Operation::Increment) // La operación de aquí es dummy, solo se usa el tipo de cambio
     } else if args.minor {
         ("minor", Operation::Increment)
@@ -134,41 +264,63 @@ pub fn get_version_change(args: &VersionArgs) -> (&'static str, Operation) {
     }
 }
 
+/// Cambio de pre-release solicitado desde la línea de comandos.
+pub enum PreReleaseChange {
+    None,
+    Start(Option<String>),
+    Promote,
+    Release,
+    Post,
+    Dev,
+}
+
+/// Determina el cambio de pre-release basado en las flags mutuamente excluyentes.
+pub fn get_pre_release_change(args: &PreReleaseArgs) -> PreReleaseChange {
+    if args.post {
+        PreReleaseChange::Post
+    } else if args.dev {
+        PreReleaseChange::Dev
+    } else if args.release {
+        PreReleaseChange::Release
+    } else if args.promote {
+        PreReleaseChange::Promote
+    } else if let Some(label) = &args.pre {
+        PreReleaseChange::Start(label.clone())
+    } else {
+        PreReleaseChange::None
+    }
+}
+
+/// Devuelve el tipo de cambio solo si el usuario lo indicó explícitamente.
+pub fn get_explicit_version_change(args: &VersionArgs) -> Option<&str> {
+    if args.major || args.minor || args.patch || args.part.is_some() {
+        Some(get_version_change(args).0)
+    } else {
+        None
+    }
+}
+
 /// Lógica SemVer: Calcula la nueva (o anterior) versión.
+///
+/// Al incrementar una pre-release se publica la versión final a la que apunta
+/// (`1.2.0-rc.1` + minor -> `1.2.0`), igual que hace npm. Los metadatos de
+/// compilación nunca se conservan en la versión resultante.
 pub fn calculate_version(
-    current_version: &str,
+    current_version: &Version,
     change_type: &str,
     operation: Operation,
-) -> Result<String, String> {
-    let parts: Vec<&str> = current_version.split('.').collect();
-    if parts.len() != 3 {
-        return Err(format!("Invalid current version format: {}", current_version));
-    }
-
-    let mut major = parts[0]
-        .parse::<i32>()
-        .map_err(|_| "Error parsing major component".to_string())?;
-    let mut minor = parts[1]
-        .parse::<i32>()
-        .map_err(|_| "Error parsing minor component".to_string())?;
-    let mut patch = parts[2]
-        .parse::<i32>()
-        .map_err(|_| "Error parsing patch component".to_string())?;
-
-    match operation {
+) -> Result<Version, String> {
+    let Version { major, minor, patch, .. } = *current_version;
+    let is_prerelease = current_version.is_prerelease();
+
+    let (major, minor, patch) = match operation {
         Operation::Increment => match change_type {
-            "major" => {
-                major += 1;
-                minor = 0;
-                patch = 0;
-            }
-            "minor" => {
-                minor += 1;
-                patch = 0;
-            }
-            "patch" => {
-                patch += 1;
-            }
+            "major" if is_prerelease && minor == 0 && patch == 0 => (major, 0, 0),
+            "major" => (major + 1, 0, 0),
+            "minor" if is_prerelease && patch == 0 => (major, minor, 0),
+            "minor" => (major, minor + 1, 0),
+            "patch" if is_prerelease => (major, minor, patch),
+            "patch" => (major, minor, patch + 1),
             _ => return Err(format!("Unknown change type: {}", change_type)),
         },
         Operation::Decrement => match change_type {
@@ -176,9 +328,7 @@ pub fn calculate_version(
                 if major == 0 {
                     return Err("Cannot downgrade major version 0".to_string());
                 }
-                major -= 1;
-                minor = 0;
-                patch = 0;
+                (major - 1, 0, 0)
             }
             "minor" => {
                 if minor == 0 && major == 0 {
@@ -188,8 +338,7 @@ pub fn calculate_version(
                         "Cannot downgrade minor 0 without explicitly specifying --major".to_string(),
                     );
                 }
-                minor -= 1;
-                patch = 0;
+                (major, minor - 1, 0)
             }
             "patch" => {
                 if patch == 0 && minor == 0 && major == 0 {
@@ -199,26 +348,502 @@ pub fn calculate_version(
                         "Cannot downgrade patch 0 without explicitly specifying --minor or --major".to_string(),
                     );
                 }
-                patch -= 1;
+                (major, minor, patch - 1)
             }
             _ => return Err(format!("Unknown change type: {}", change_type)),
         },
+    };
+
+    Ok(Version::new(major, minor, patch))
+}
+
+/// Lógica de pre-releases: inicia, incrementa, promociona o publica una pre-release.
+///
+/// - `Start` con un tipo de cambio explícito (o sobre una versión final) inicia la
+///   pre-release `<label>.1` sobre el siguiente patch/minor/major.
+/// - `Start` sobre una pre-release sin tipo de cambio incrementa su contador, o cambia
+///   a otra etiqueta posterior reiniciando el contador.
+/// - `Promote` pasa a la siguiente etiqueta de `labels`.
+/// - `Release` elimina la parte de pre-release.
+pub fn calculate_pre_release(
+    current_version: &Version,
+    change_type: Option<&str>,
+    change: PreReleaseChange,
+    labels: &[String],
+) -> Result<Version, String> {
+    let release = Version::new(
+        current_version.major,
+        current_version.minor,
+        current_version.patch,
+    );
+    let current_label = match current_version.pre.first() {
+        Some(Identifier::AlphaNumeric(label)) => Some(label.as_str()),
+        _ => None,
+    };
+
+    match change {
+        PreReleaseChange::None => calculate_version(
+            current_version,
+            change_type.unwrap_or("patch"),
+            Operation::Increment,
+        ),
+        PreReleaseChange::Release => {
+            if !current_version.is_prerelease() {
+                return Err(format!("Version {} is not a pre-release", current_version));
+            }
+            Ok(release)
+        }
+        PreReleaseChange::Post | PreReleaseChange::Dev => {
+            Err("--post and --dev are only supported by the pep440 scheme".to_string())
+        }
+        PreReleaseChange::Promote => {
+            let label = current_label
+                .ok_or_else(|| format!("Version {} has no pre-release label to promote", current_version))?;
+            let position = labels
+                .iter()
+                .position(|l| l == label)
+                .ok_or_else(|| format!("Pre-release label '{}' is not listed in pre_release_labels", label))?;
+            let next = labels.get(position + 1).ok_or_else(|| {
+                format!("'{}' is the last pre-release label; use --release to publish the final version", label)
+            })?;
+            with_pre_release(release, next)
+        }
+        PreReleaseChange::Start(label) => {
+            if change_type.is_some() || !current_version.is_prerelease() {
+                let label = match label.as_deref().or(labels.first().map(String::as_str)) {
+                    Some(label) => label,
+                    None => return Err("No pre-release label given and pre_release_labels is empty".to_string()),
+                };
+                let base = calculate_version(&release, change_type.unwrap_or("patch"), Operation::Increment)?;
+                return with_pre_release(base, label);
+            }
+
+            match label.as_deref() {
+                Some(label) if Some(label) != current_label => {
+                    let target = with_pre_release(release, label)?;
+                    let goes_forward = match (
+                        current_label.and_then(|c| labels.iter().position(|l| l == c)),
+                        labels.iter().position(|l| l == label),
+                    ) {
+                        (Some(from), Some(to)) => to > from,
+                        _ => target.cmp_precedence(current_version).is_gt(),
+                    };
+                    if !goes_forward {
+                        return Err(format!(
+                            "Pre-release '{}' would go backwards from {}",
+                            label, current_version
+                        ));
+                    }
+                    Ok(target)
+                }
+                _ => {
+                    // Misma etiqueta: se incrementa el último contador numérico.
+                    let mut next = release;
+                    next.pre = current_version.pre.clone();
+                    match next.pre.iter_mut().rev().find_map(|i| match i {
+                        Identifier::Numeric(n) => Some(n),
+                        Identifier::AlphaNumeric(_) => None,
+                    }) {
+                        Some(counter) => *counter += 1,
+                        None => next.pre.push(Identifier::Numeric(1)),
+                    }
+                    Ok(next)
+                }
+            }
+        }
     }
+}
 
-    if major < 0 || minor < 0 || patch < 0 {
-        return Err("Invalid (negative) version result".to_string());
+fn with_pre_release(mut version: Version, label: &str) -> Result<Version, String> {
+    if label.is_empty()
+        || label.chars().all(|c| c.is_ascii_digit())
+        || !label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+    {
+        return Err(format!("Invalid pre-release label '{}'", label));
     }
+    version.pre = vec![
+        Identifier::AlphaNumeric(label.to_string()),
+        Identifier::Numeric(1),
+    ];
+    Ok(version)
+}
 
-    Ok(format!("{}.{}.{}", major, minor, patch))
+// =============================================================================================
+// ESQUEMAS DE VERSIONADO
+// =============================================================================================
+
+/// Valida `version` según el esquema configurado y la devuelve normalizada.
+pub fn validate_version(config: &Config, version: &str) -> Result<String, String> {
+    match config.scheme {
+        Scheme::SemVer => version.parse::<Version>().map(|v| v.to_string()),
+        Scheme::CalVer => config.calver()?.validate(version),
+        Scheme::Custom => config.format()?.validate(version),
+        Scheme::Pep440 => version.parse::<Pep440Version>().map(|v| v.to_string()),
+    }
 }
 
-/// Obtiene la ruta del archivo de configuración.
+/// Descompone `version` en sus componentes con nombre (`major`, `minor`, `patch`...) para
+/// las plantillas. En CalVer no hay componentes; si la versión no es válida, tampoco.
+pub fn version_components(config: &Config, version: &str) -> Vec<(String, String)> {
+    let components = match config.scheme {
+        Scheme::SemVer => version.parse::<Version>().map(|v| {
+            let pre: Vec<String> = v.pre.iter().map(|i| i.to_string()).collect();
+            vec![
+                ("major".to_string(), v.major.to_string()),
+                ("minor".to_string(), v.minor.to_string()),
+                ("patch".to_string(), v.patch.to_string()),
+                ("prerelease".to_string(), pre.join(".")),
+                ("build".to_string(), v.build.join(".")),
+            ]
+        }),
+        Scheme::Pep440 => version.parse::<Pep440Version>().map(|v| {
+            ["major", "minor", "patch"]
+                .iter()
+                .enumerate()
+                .map(|(i, name)| (name.to_string(), v.release.get(i).copied().unwrap_or(0).to_string()))
+                .collect()
+        }),
+        Scheme::Custom => config.format().and_then(|format| format.parts(version)).map(|parts| {
+            parts
+                .into_iter()
+                .map(|(name, value)| (name, value.to_string()))
+                .collect()
+        }),
+        Scheme::CalVer => Ok(Vec::new()),
+    };
+    components.unwrap_or_default()
+}
+
+/// Compara dos versiones según el orden del esquema configurado.
+pub fn compare_versions(config: &Config, a: &str, b: &str) -> Result<Ordering, String> {
+    match config.scheme {
+        Scheme::SemVer => Ok(a
+            .parse::<Version>()?
+            .cmp_precedence(&b.parse::<Version>()?)),
+        Scheme::CalVer => config.calver()?.compare(a, b),
+        Scheme::Custom => config.format()?.compare(a, b),
+        Scheme::Pep440 => Ok(a
+            .parse::<Pep440Version>()?
+            .cmp(&b.parse::<Pep440Version>()?)),
+    }
+}
+
+/// Indica si la versión actual está en desarrollo inicial (major 0), donde los cambios
+/// incompatibles solo incrementan el minor.
+pub fn is_initial_development(config: &Config) -> bool {
+    match config.scheme {
+        Scheme::SemVer => config.version().is_ok_and(|v| v.major == 0),
+        Scheme::Pep440 => config
+            .current_version
+            .parse::<Pep440Version>()
+            .is_ok_and(|v| v.release.first() == Some(&0)),
+        Scheme::CalVer | Scheme::Custom => false,
+    }
+}
+
+/// Resuelve el tipo de cambio de `Upgrade`/`Preview`. Con `--auto` se deduce de los
+/// Conventional Commits desde la última etiqueta y se devuelve también el análisis.
+pub async fn resolve_version_change<'a>(
+    config: &Config,
+    args: &'a VersionArgs,
+) -> Result<(Option<&'a str>, Option<AutoChange>), String> {
+    if args.auto {
+        let tag = latest_version_tag(config).await.map(|(tag, _)| tag);
+        let auto_change = infer_version_change(tag, is_initial_development(config)).await?;
+        Ok((auto_change.change_type, Some(auto_change)))
+    } else {
+        Ok((get_explicit_version_change(args), None))
+    }
+}
+
+/// Calcula la siguiente versión (Upgrade/Preview) según el esquema configurado.
+/// `today` es la fecha usada por CalVer, inyectada para poder fijarla.
+pub fn calculate_next_version(
+    config: &Config,
+    change_type: Option<&str>,
+    pre_release_change: PreReleaseChange,
+    today: NaiveDate,
+) -> Result<String, String> {
+
+    match config.scheme {
+        Scheme::SemVer => calculate_pre_release(
+            &config.version()?,
+            change_type,
+            pre_release_change,
+            &config.pre_release_labels,
+        )
+        .map(|v| v.to_string()),
+        Scheme::CalVer => {
+            if change_type.is_some() || !matches!(pre_release_change, PreReleaseChange::None) {
+                return Err(
+                    "The calver scheme derives the next version from the date; --major, --minor, --patch and pre-release options are not supported".to_string(),
+                );
+            }
+            config.calver()?.next_version(&config.current_version, today)
+        }
+        Scheme::Custom => {
+            if !matches!(pre_release_change, PreReleaseChange::None) {
+                return Err("Pre-release options are not supported by the custom scheme".to_string());
+            }
+            config.format()?.bump(
+                &config.current_version,
+                change_type.unwrap_or("patch"),
+                Operation::Increment,
+            )
+        }
+        Scheme::Pep440 => config
+            .current_version
+            .parse::<Pep440Version>()?
+            .bump(change_type, pre_release_change)
+            .map(|v| v.to_string()),
+    }
+}
+
+/// Valida la versión explícita de `Set` y comprueba que no retrocede (salvo con `force`).
+pub fn calculate_target_version(config: &Config, target_version: &str, force: bool) -> Result<String, String> {
+    let current_version = validate_version(config, &config.current_version)?;
+    let target_version = validate_version(config, target_version)
+        .map_err(|e| format!("Invalid target version: {}", e))?;
+    let goes_forward = compare_versions(config, &target_version, &current_version)
+        .is_ok_and(|ordering| ordering.is_gt());
+    if !goes_forward && !force {
+        return Err(format!(
+            "Target version {} is not greater than the current version {}. Use --force to set it anyway.",
+            target_version, current_version
+        ));
+    }
+    Ok(target_version)
+}
+
+/// Calcula la versión anterior (Downgrade) según el esquema configurado.
+pub fn calculate_previous_version(config: &Config, args: &VersionArgs) -> Result<String, String> {
+    if args.auto {
+        return Err("--auto is only supported by upgrade and preview".to_string());
+    }
+    let (change_type, _) = get_version_change(args);
+
+    match config.scheme {
+        Scheme::SemVer => calculate_version(&config.version()?, change_type, Operation::Decrement)
+            .map(|v| v.to_string()),
+        Scheme::CalVer => Err(
+            "Downgrade is not supported by the calver scheme; use 'set' instead".to_string(),
+        ),
+        Scheme::Custom => config
+            .format()?
+            .bump(&config.current_version, change_type, Operation::Decrement),
+        Scheme::Pep440 => config
+            .current_version
+            .parse::<Pep440Version>()?
+            .decrement(change_type)
+            .map(|v| v.to_string()),
+    }
+}
+
+// =============================================================================================
+// ORIGEN DE LA VERSIÓN ACTUAL
+// =============================================================================================
+
+/// Versión actual resuelta junto con su origen real.
+pub struct ResolvedVersion {
+    pub version: String,
+    pub source: VersionSource,
+    pub tag: Option<String>,
+}
+
+/// Resuelve la versión actual. Con `version_source: git` se usa la etiqueta con la versión
+/// más alta que empiece por `tag_prefix` y sea alcanzable desde HEAD; si no hay ninguna
+/// válida, se recurre a `current_version`.
+pub async fn resolve_current_version(config: &Config) -> ResolvedVersion {
+    let from_config = ResolvedVersion {
+        version: config.current_version.clone(),
+        source: VersionSource::Config,
+        tag: None,
+    };
+    if config.version_source == VersionSource::Config {
+        return from_config;
+    }
+
+    match latest_version_tag(config).await {
+        Some((tag, version)) => ResolvedVersion {
+            version,
+            source: VersionSource::Git,
+            tag: Some(tag),
+        },
+        None => {
+            debug!("No version tags matching '{}*' found, using the config version", config.tag_prefix);
+            from_config
+        }
+    }
+}
+
+/// Devuelve la etiqueta (y su versión) con la versión más alta que empiece por `tag_prefix`
+/// y sea alcanzable desde HEAD. Las etiquetas que no son versiones válidas se ignoran.
+pub async fn latest_version_tag(config: &Config) -> Option<(String, String)> {
+    let tags = match git::merged_tags(&config.tag_prefix).await {
+        Ok(tags) => tags,
+        Err(e) => {
+            debug!("Could not read git tags: {}", e);
+            return None;
+        }
+    };
+
+    let mut highest: Option<(String, String)> = None;
+    for tag in tags {
+        let Ok(version) = validate_version(config, &tag[config.tag_prefix.len()..]) else {
+            debug!("Ignoring tag '{}': not a valid version", tag);
+            continue;
+        };
+        let is_higher = match &highest {
+            Some((_, best)) => compare_versions(config, &version, best).is_ok_and(|o| o.is_gt()),
+            None => true,
+        };
+        if is_higher {
+            highest = Some((tag, version));
+        }
+    }
+    highest
+}
+
+// =============================================================================================
+// EXPANSIÓN DE ARCHIVOS (GLOBS)
+// =============================================================================================
+
+fn is_glob(pattern: &str) -> bool {
+    pattern.contains(['*', '?', '['])
+}
+
+fn normalize_path(path: &str) -> &str {
+    path.strip_prefix("./").unwrap_or(path)
+}
+
+// `*` no cruza directorios; `**` sí.
+fn matches_glob(pattern: &glob::Pattern, path: &str) -> bool {
+    let options = glob::MatchOptions {
+        require_literal_separator: true,
+        ..Default::default()
+    };
+    pattern.matches_with(path, options)
+}
+
+/// Lista los archivos bajo el directorio actual respetando `.gitignore` (también fuera de
+/// un repositorio git). Incluye archivos ocultos, pero nunca el contenido de `.git`.
+fn list_project_files() -> Vec<String> {
+    let mut files: Vec<String> = ignore::WalkBuilder::new(".")
+        .hidden(false)
+        .require_git(false)
+        .filter_entry(|entry| entry.file_name() != ".git")
+        .build()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_some_and(|t| t.is_file()))
+        .filter_map(|entry| entry.path().to_str().map(|path| normalize_path(path).to_string()))
+        .collect();
+    files.sort();
+    files
+}
+
+/// Expande el `file` de una regla `Replace` a rutas concretas, descartando las que
+/// coinciden con `exclude`. Las rutas literales se mantienen aunque no existan (la
+/// simulación informará del error); un glob que no encuentra ningún archivo es un error.
+pub fn expand_replace_files(replace: &Replace) -> Result<Vec<String>, String> {
+    let excludes = replace
+        .exclude
+        .iter()
+        .map(|pattern| {
+            glob::Pattern::new(normalize_path(pattern))
+                .map_err(|e| format!("Invalid exclude pattern '{}': {}", pattern, e))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let is_excluded = |path: &str| excludes.iter().any(|pattern| matches_glob(pattern, path));
+
+    let mut project_files: Option<Vec<String>> = None;
+    let mut files: Vec<String> = Vec::new();
+
+    for pattern in replace.file.patterns() {
+        let normalized = normalize_path(pattern);
+        if !is_glob(normalized) {
+            if !is_excluded(normalized) && !files.iter().any(|f| f == normalized) {
+                files.push(normalized.to_string());
+            }
+            continue;
+        }
+
+        let glob = glob::Pattern::new(normalized)
+            .map_err(|e| format!("Invalid file pattern '{}': {}", pattern, e))?;
+        let candidates = project_files.get_or_insert_with(list_project_files);
+        let matched: Vec<&String> = candidates
+            .iter()
+            .filter(|path| matches_glob(&glob, path) && !is_excluded(path))
+            .collect();
+        if matched.is_empty() {
+            return Err(format!(
+                "File pattern '{}' did not match any file (after applying .gitignore and exclude)",
+                pattern
+            ));
+        }
+        debug!("File pattern '{}' expanded to {} file(s)", pattern, matched.len());
+        for path in matched {
+            if !files.contains(path) {
+                files.push(path.clone());
+            }
+        }
+    }
+
+    Ok(files)
+}
+
+// =============================================================================================
+// COMPROBACIONES PREVIAS (GIT)
+// =============================================================================================
+
+fn matches_any(patterns: &[String], value: &str) -> bool {
+    patterns.iter().any(|pattern| {
+        glob::Pattern::new(pattern)
+            .map(|p| p.matches(value))
+            .unwrap_or_else(|_| pattern == value)
+    })
+}
+
+/// Comprueba, antes de tocar ningún archivo, que el árbol de trabajo no tiene cambios sin
+/// confirmar (salvo los de `allow_dirty` o si se pasa `--allow-dirty`) y que la rama actual
+/// está en `allowed_branches`. Fuera de un repositorio git no se comprueba nada.
+pub async fn run_preflight_checks(config: &Config, allow_dirty: bool) -> Result<(), String> {
+    if !git::is_repository().await {
+        debug!("Not inside a git repository. Skipping pre-flight checks.");
+        return Ok(());
+    }
+
+    if !config.allowed_branches.is_empty() {
+        let branch = git::current_branch().await?;
+        if !matches_any(&config.allowed_branches, &branch) {
+            return Err(format!(
+                "Branch '{}' is not in allowed_branches ({})",
+                branch,
+                config.allowed_branches.join(", ")
+            ));
+        }
+    }
+
+    if allow_dirty {
+        return Ok(());
+    }
+
+    let dirty: Vec<String> = git::uncommitted_files()
+        .await?
+        .into_iter()
+        .filter(|file| !matches_any(&config.allow_dirty, file))
+        .collect();
+    if !dirty.is_empty() {
+        return Err(format!(
+            "The working tree has uncommitted changes: {}. Commit or stash them, or use --allow-dirty",
+            dirty.join(", ")
+        ));
+    }
+    Ok(())
+}
+
+/// Obtiene la ruta del archivo de configuración (`.vampus.yml` en el directorio actual).
 pub async fn get_config_path() -> PathBuf {
     let mut config_path = env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
     config_path.push(format!(".{}.yml", APP_NAME));
-
-    if !config_path.exists() {
-        config::Config::write_default(&config_path).await;
-    }
     config_path
 }
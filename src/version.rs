@@ -0,0 +1,263 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::{cmp::Ordering, fmt, str::FromStr};
+
+// =============================================================================================
+// MODELO DE VERSIÓN (SemVer 2.0)
+// =============================================================================================
+
+/// Identificador de pre-release: numérico o alfanumérico (SemVer 2.0, punto 9).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Identifier {
+    Numeric(u64),
+    AlphaNumeric(String),
+}
+
+impl Identifier {
+    fn parse(part: &str) -> Result<Self, String> {
+        if part.is_empty() {
+            return Err("Empty pre-release identifier".to_string());
+        }
+        if !part.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+            return Err(format!("Invalid characters in pre-release identifier '{}'", part));
+        }
+        if part.chars().all(|c| c.is_ascii_digit()) {
+            if part.len() > 1 && part.starts_with('0') {
+                return Err(format!("Numeric identifier '{}' must not have leading zeros", part));
+            }
+            let value = part
+                .parse::<u64>()
+                .map_err(|_| format!("Numeric identifier '{}' is too large", part))?;
+            Ok(Identifier::Numeric(value))
+        } else {
+            Ok(Identifier::AlphaNumeric(part.to_string()))
+        }
+    }
+}
+
+impl fmt::Display for Identifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Identifier::Numeric(n) => write!(f, "{}", n),
+            Identifier::AlphaNumeric(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+impl PartialOrd for Identifier {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Identifier {
+    /// Los numéricos se comparan como enteros, los alfanuméricos en orden ASCII
+    /// y los numéricos siempre tienen menor precedencia que los alfanuméricos.
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Identifier::Numeric(a), Identifier::Numeric(b)) => a.cmp(b),
+            (Identifier::Numeric(_), Identifier::AlphaNumeric(_)) => Ordering::Less,
+            (Identifier::AlphaNumeric(_), Identifier::Numeric(_)) => Ordering::Greater,
+            (Identifier::AlphaNumeric(a), Identifier::AlphaNumeric(b)) => a.cmp(b),
+        }
+    }
+}
+
+/// Versión SemVer 2.0: `MAJOR.MINOR.PATCH[-PRERELEASE][+BUILD]`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Version {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+    pub pre: Vec<Identifier>,
+    pub build: Vec<String>,
+}
+
+impl Version {
+    pub fn new(major: u64, minor: u64, patch: u64) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+            pre: Vec::new(),
+            build: Vec::new(),
+        }
+    }
+
+    pub fn is_prerelease(&self) -> bool {
+        !self.pre.is_empty()
+    }
+
+    /// Compara dos versiones según las reglas de precedencia de SemVer 2.0,
+    /// ignorando los metadatos de compilación.
+    pub fn cmp_precedence(&self, other: &Self) -> Ordering {
+        (self.major, self.minor, self.patch)
+            .cmp(&(other.major, other.minor, other.patch))
+            .then_with(|| match (self.pre.is_empty(), other.pre.is_empty()) {
+                (true, true) => Ordering::Equal,
+                // Una versión sin pre-release tiene mayor precedencia.
+                (true, false) => Ordering::Greater,
+                (false, true) => Ordering::Less,
+                (false, false) => self.pre.cmp(&other.pre),
+            })
+    }
+}
+
+fn parse_numeric(part: &str, name: &str) -> Result<u64, String> {
+    if part.is_empty() || !part.chars().all(|c| c.is_ascii_digit()) {
+        return Err(format!("Error parsing {} component '{}'", name, part));
+    }
+    if part.len() > 1 && part.starts_with('0') {
+        return Err(format!("The {} component '{}' must not have leading zeros", name, part));
+    }
+    part.parse::<u64>()
+        .map_err(|_| format!("The {} component '{}' is too large", name, part))
+}
+
+impl FromStr for Version {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let input = s.trim();
+        let (rest, build) = match input.split_once('+') {
+            Some((rest, build)) => (rest, Some(build)),
+            None => (input, None),
+        };
+        let (core, pre) = match rest.split_once('-') {
+            Some((core, pre)) => (core, Some(pre)),
+            None => (rest, None),
+        };
+
+        let parts: Vec<&str> = core.split('.').collect();
+        if parts.len() != 3 {
+            return Err(format!("Invalid version format: {}", input));
+        }
+
+        let pre = match pre {
+            Some(pre) => pre
+                .split('.')
+                .map(Identifier::parse)
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| format!("Invalid version '{}': {}", input, e))?,
+            None => Vec::new(),
+        };
+
+        let build = match build {
+            Some(build) => build
+                .split('.')
+                .map(|part| {
+                    if part.is_empty()
+                        || !part.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+                    {
+                        Err(format!("Invalid build metadata '{}' in version '{}'", part, input))
+                    } else {
+                        Ok(part.to_string())
+                    }
+                })
+                .collect::<Result<Vec<_>, _>>()?,
+            None => Vec::new(),
+        };
+
+        Ok(Self {
+            major: parse_numeric(parts[0], "major")?,
+            minor: parse_numeric(parts[1], "minor")?,
+            patch: parse_numeric(parts[2], "patch")?,
+            pre,
+            build,
+        })
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)?;
+        if !self.pre.is_empty() {
+            let pre: Vec<String> = self.pre.iter().map(|i| i.to_string()).collect();
+            write!(f, "-{}", pre.join("."))?;
+        }
+        if !self.build.is_empty() {
+            write!(f, "+{}", self.build.join("."))?;
+        }
+        Ok(())
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Version {
+    /// Orden total: primero la precedencia SemVer y, en caso de empate, los
+    /// metadatos de compilación para mantener la coherencia con `Eq`.
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.cmp_precedence(other)
+            .then_with(|| self.build.cmp(&other.build))
+    }
+}
+
+impl Serialize for Version {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Version {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn version(s: &str) -> Version {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn precedence_follows_spec_example() {
+        let chain = [
+            "1.0.0-alpha",
+            "1.0.0-alpha.1",
+            "1.0.0-alpha.beta",
+            "1.0.0-beta",
+            "1.0.0-beta.2",
+            "1.0.0-beta.11",
+            "1.0.0-rc.1",
+            "1.0.0",
+            "2.0.0",
+            "2.1.0",
+            "2.1.1",
+        ];
+        for pair in chain.windows(2) {
+            assert!(version(pair[0]) < version(pair[1]), "{} < {}", pair[0], pair[1]);
+        }
+    }
+
+    #[test]
+    fn numeric_identifiers_sort_below_alphanumeric() {
+        assert!(Identifier::parse("999").unwrap() < Identifier::parse("a").unwrap());
+        assert!(Identifier::parse("2").unwrap() < Identifier::parse("11").unwrap());
+        assert!(Identifier::parse("-1").unwrap() > Identifier::parse("1").unwrap());
+    }
+
+    #[test]
+    fn build_metadata_is_ignored_for_precedence() {
+        let build = version("1.0.0+20130313144700");
+        assert_eq!(build.build, vec!["20130313144700"]);
+        assert_eq!(build.cmp_precedence(&version("1.0.0")), Ordering::Equal);
+        assert_eq!(version("1.0.0-beta+exp.sha.5114f85").to_string(), "1.0.0-beta+exp.sha.5114f85");
+    }
+
+    #[test]
+    fn rejects_invalid_versions() {
+        for invalid in ["01.0.0", "1.02.0", "1.0.0-01", "1.0", "1.0.0-", "1.0.0-alpha..1", "1.0.0+", "1.0.0+b@d", "v1.0.0"] {
+            assert!(invalid.parse::<Version>().is_err(), "{} should be rejected", invalid);
+        }
+        assert!("1.0.0-0.3.7".parse::<Version>().is_ok());
+        assert!("1.0.0-x-y-z.--".parse::<Version>().is_ok());
+    }
+}